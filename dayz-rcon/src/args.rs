@@ -1,5 +1,5 @@
 use std::io::{Write, stdout};
-use std::net::{IpAddr, SocketAddr};
+use std::net::IpAddr;
 use std::time::Duration;
 
 use clap::{Parser, Subcommand};
@@ -11,8 +11,11 @@ use uuid::Uuid;
 #[derive(Debug, Parser)]
 #[command(author, version, about = "An RCon CLI client.")]
 pub struct Args {
-    #[arg(index = 1, help = "The server address to connect to")]
-    server: SocketAddr,
+    #[arg(
+        index = 1,
+        help = "The server address to connect to, e.g. \"example.org:2302\""
+    )]
+    server: String,
 
     #[arg(short, long, help = "The password for the RCON server")]
     password: Option<String>,
@@ -24,8 +27,8 @@ pub struct Args {
 impl Args {
     /// Return the server socket.
     #[must_use]
-    pub const fn server(&self) -> SocketAddr {
-        self.server
+    pub fn server(&self) -> &str {
+        &self.server
     }
 
     /// Return the password.
@@ -102,10 +105,10 @@ impl Command {
                 .players()
                 .await
                 .map(|players| players.iter().for_each(|player| println!("{player}"))),
-            Self::Say { player, message } => client.say(player, &message).await.map_err(Into::into),
-            Self::Broadcast { message } => client.broadcast(&message).await.map_err(Into::into),
-            Self::Kick { player, reason } => client.kick(player, reason).await.map_err(Into::into),
-            Self::Ban { player, reason } => client.ban(player, reason).await.map_err(Into::into),
+            Self::Say { player, message } => client.say(player, &message).await,
+            Self::Broadcast { message } => client.broadcast(&message).await,
+            Self::Kick { player, reason } => client.kick(player, reason).await,
+            Self::Ban { player, reason } => client.ban(player, reason).await,
             Self::Bans => client
                 .bans()
                 .await
@@ -122,12 +125,11 @@ impl Command {
                 )
                 .await
                 .map_err(Into::into),
-            Self::RemoveBan { id } => client.remove_ban(id).await.map_err(Into::into),
+            Self::RemoveBan { id } => client.remove_ban(id).await,
             Self::Exec { command } => client
                 .run(command.join(" "))
                 .await
-                .and_then(|result| stdout().lock().write_all(&result))
-                .map_err(Into::into),
+                .and_then(|result| Ok(stdout().lock().write_all(&result)?)),
         }
     }
 }
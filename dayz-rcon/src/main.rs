@@ -5,8 +5,8 @@ use std::process::ExitCode;
 
 use clap::Parser;
 use log::error;
-use rcon::RCon;
-use rcon::battleye::Client;
+use rcon::battleye::Connection;
+use rcon::{Connect, Error};
 use rpassword::prompt_password;
 
 use self::args::Args;
@@ -18,8 +18,8 @@ async fn main() -> ExitCode {
     env_logger::init();
     let args = Args::parse();
 
-    let mut client = match Client::connect(args.server()).await {
-        Ok(client) => client,
+    let connection = match Connection::connect(args.server()).await {
+        Ok(connection) => connection,
         Err(error) => {
             error!("{error}");
             return ExitCode::from(1);
@@ -38,18 +38,17 @@ async fn main() -> ExitCode {
         }
     };
 
-    match client.login(password.as_bytes()).await {
-        Ok(logged_in) => {
-            if !logged_in {
-                error!("Login failed.");
-                return ExitCode::from(4);
-            }
+    let mut client = match connection.login(password.as_bytes()).await {
+        Ok(client) => client,
+        Err(Error::AuthenticationFailed) => {
+            error!("Login failed.");
+            return ExitCode::from(4);
         }
         Err(error) => {
             error!("{error}");
             return ExitCode::from(3);
         }
-    }
+    };
 
     if let Err(error) = args.run(&mut client).await {
         error!("{error}");
@@ -0,0 +1,46 @@
+//! A mock `DayZ` server to run `BattlEye Rcon` clients against.
+//!
+//! Usage: `dayz_server [ADDRESS] [PASSWORD]`
+
+// Examples are linked against all dependencies of the library.
+#![allow(unused_crate_dependencies)]
+
+use std::env::args;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use rcon::dayz::mock::MockServer;
+use rcon::dayz::{Player, Target};
+use uuid::Uuid;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:2302";
+const DEFAULT_PASSWORD: &str = "password";
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), rcon::Error> {
+    let mut args = args().skip(1);
+    let address = args.next().unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+    let password = args.next().unwrap_or_else(|| DEFAULT_PASSWORD.to_string());
+
+    let server = MockServer::new()
+        .with_player(Player::new(
+            0,
+            SocketAddr::from(([192, 168, 0, 42], 2304)),
+            Duration::from_millis(31),
+            Uuid::from_u128(0x0123_4567_89ab_cdef_0123_4567_89ab_cdef),
+            "John Doe".to_string(),
+        ))
+        .with_player(Player::new(
+            1,
+            SocketAddr::from(([10, 0, 0, 1], 2316)),
+            Duration::from_millis(120),
+            Uuid::from_u128(0xfedc_ba98_7654_3210_fedc_ba98_7654_3210),
+            "Jane".to_string(),
+        ))
+        .with_ban(Target::Ip([192, 168, 0, 66].into()), None, Some("Cheating"))
+        .bind(&address, password)
+        .await?;
+
+    println!("Listening on {}", server.local_addr()?);
+    server.serve().await
+}
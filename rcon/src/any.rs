@@ -0,0 +1,196 @@
+//! Protocol-agnostic clients, selectable at runtime.
+//!
+//! The protocol can be chosen via [`Protocol`] or from a URL via [`Config`].
+
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use tokio::net::ToSocketAddrs;
+
+pub use self::config::Config;
+#[cfg(feature = "battleye")]
+use crate::battleye;
+#[cfg(feature = "source")]
+use crate::source;
+use crate::{Connect, Error, RCon};
+
+mod config;
+
+/// Supported `RCON` protocols.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Protocol {
+    /// The `BattlEye Rcon` protocol.
+    #[cfg(feature = "battleye")]
+    BattlEye,
+    /// The `Source RCON` protocol.
+    #[cfg(feature = "source")]
+    Source,
+}
+
+impl Display for Protocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "battleye")]
+            Self::BattlEye => write!(f, "battleye"),
+            #[cfg(feature = "source")]
+            Self::Source => write!(f, "source"),
+        }
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            #[cfg(feature = "battleye")]
+            "battleye" => Ok(Self::BattlEye),
+            #[cfg(feature = "source")]
+            "source" => Ok(Self::Source),
+            _ => Err(format!("Unsupported protocol: {s}")),
+        }
+    }
+}
+
+/// An unauthenticated connection using any of the supported protocols.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AnyConnection {
+    /// A `BattlEye Rcon` connection.
+    #[cfg(feature = "battleye")]
+    BattlEye(battleye::Connection),
+    /// A `Source RCON` connection.
+    #[cfg(feature = "source")]
+    Source(source::Connection),
+}
+
+impl AnyConnection {
+    /// Connect to the specified address using the given protocol and default settings.
+    ///
+    /// Use the protocol-specific builders to customize the connection and convert it afterward.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the address cannot be resolved or no connection could be established.
+    pub async fn connect<T>(protocol: Protocol, address: T) -> Result<Self, Error>
+    where
+        T: ToSocketAddrs + Send,
+    {
+        match protocol {
+            #[cfg(feature = "battleye")]
+            Protocol::BattlEye => battleye::Connection::connect(address).await.map(Self::from),
+            #[cfg(feature = "source")]
+            Protocol::Source => source::Connection::connect(address).await.map(Self::from),
+        }
+    }
+
+    /// Returns the protocol of the connection.
+    #[must_use]
+    pub const fn protocol(&self) -> Protocol {
+        match self {
+            #[cfg(feature = "battleye")]
+            Self::BattlEye(_) => Protocol::BattlEye,
+            #[cfg(feature = "source")]
+            Self::Source(_) => Protocol::Source,
+        }
+    }
+
+    /// Perform a login, consuming the connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AuthenticationFailed`] if the server rejected the password.
+    /// Returns another [`Error`] if any I/O or protocol errors occurred.
+    pub async fn login<T>(self, password: T) -> Result<AnyClient, Error>
+    where
+        T: AsRef<[u8]> + Send,
+    {
+        match self {
+            #[cfg(feature = "battleye")]
+            Self::BattlEye(connection) => connection.login(password).await.map(AnyClient::from),
+            #[cfg(feature = "source")]
+            Self::Source(connection) => connection.login(password).await.map(AnyClient::from),
+        }
+    }
+}
+
+#[cfg(feature = "battleye")]
+impl From<battleye::Connection> for AnyConnection {
+    fn from(connection: battleye::Connection) -> Self {
+        Self::BattlEye(connection)
+    }
+}
+
+#[cfg(feature = "source")]
+impl From<source::Connection> for AnyConnection {
+    fn from(connection: source::Connection) -> Self {
+        Self::Source(connection)
+    }
+}
+
+/// An authenticated client using any of the supported protocols.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AnyClient {
+    /// A `BattlEye Rcon` client.
+    #[cfg(feature = "battleye")]
+    BattlEye(battleye::Client),
+    /// A `Source RCON` client.
+    #[cfg(feature = "source")]
+    Source(source::Client),
+}
+
+impl AnyClient {
+    /// Returns the protocol of the client.
+    #[must_use]
+    pub const fn protocol(&self) -> Protocol {
+        match self {
+            #[cfg(feature = "battleye")]
+            Self::BattlEye(_) => Protocol::BattlEye,
+            #[cfg(feature = "source")]
+            Self::Source(_) => Protocol::Source,
+        }
+    }
+}
+
+#[cfg(feature = "battleye")]
+impl From<battleye::Client> for AnyClient {
+    fn from(client: battleye::Client) -> Self {
+        Self::BattlEye(client)
+    }
+}
+
+#[cfg(feature = "source")]
+impl From<source::Client> for AnyClient {
+    fn from(client: source::Client) -> Self {
+        Self::Source(client)
+    }
+}
+
+impl RCon for AnyClient {
+    async fn run<T>(&mut self, command: T) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]> + Send,
+    {
+        match self {
+            #[cfg(feature = "battleye")]
+            Self::BattlEye(client) => client.run(command).await,
+            #[cfg(feature = "source")]
+            Self::Source(client) => client.run(command).await,
+        }
+    }
+
+    async fn run_with_timeout<T>(&mut self, command: T, timeout: Duration) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]> + Send,
+    {
+        match self {
+            #[cfg(feature = "battleye")]
+            Self::BattlEye(client) => client.run_with_timeout(command, timeout).await,
+            #[cfg(feature = "source")]
+            Self::Source(client) => client.run_with_timeout(command, timeout).await,
+        }
+    }
+}
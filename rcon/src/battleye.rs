@@ -1,14 +1,27 @@
-//! Client implementation of the [`BattlEye Rcon`](https://www.battleye.com/downloads/BERConProtocol.txt) protocol.
+//! Client and server implementation of the [`BattlEye Rcon`](https://www.battleye.com/downloads/BERConProtocol.txt) protocol.
 
-pub use self::client::Client;
+pub use self::client::{Client, ClientBuilder};
+pub use self::connection::Connection;
+pub use self::server_message::ServerMessage;
+pub use self::session_event::SessionEvent;
 use crate::RCon;
 
+#[cfg(feature = "blocking")]
+pub(crate) mod blocking;
 mod client;
-mod from_server;
-mod header;
-mod packet;
+#[cfg(feature = "battleye-codec")]
+pub mod codec;
+mod connection;
+pub mod header;
+pub mod packet;
+#[cfg(feature = "server")]
+pub mod server;
+mod server_message;
+mod session_event;
 
 /// Trait to identify `BattlEye RCon` clients.
 pub trait BattlEye: RCon {}
 
 impl BattlEye for Client {}
+
+impl BattlEye for crate::pool::Guard<Connection> {}
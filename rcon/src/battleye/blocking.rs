@@ -0,0 +1,443 @@
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::Duration;
+
+use log::{debug, trace, warn};
+
+use super::client::handler::unspecified;
+use super::client::reassembly::Reassembly;
+use super::packet::server::Ack;
+use super::packet::{Response, command, login};
+use crate::Error;
+use crate::blocking::util::{DEFAULT_TIMEOUT, Deadline, connect_any, resolve, timed_out};
+use crate::blocking::{Connect, RCon};
+
+/// Maximum size of a UDP datagram's payload.
+const BUF_SIZE: usize = 65_507;
+
+/// An unauthenticated blocking `BattlEye Rcon` connection.
+///
+/// Log in to obtain a [`Client`].
+#[derive(Debug)]
+pub struct Connection {
+    target: Target,
+    timeout: Option<Duration>,
+}
+
+impl Connection {
+    /// Create a connection on an already connected UDP socket.
+    #[must_use]
+    pub fn new(udp_socket: UdpSocket) -> Self {
+        Self {
+            target: Target::Transport(Transport::new(udp_socket)),
+            timeout: Some(DEFAULT_TIMEOUT),
+        }
+    }
+
+    /// Set the default timeout for the login and commands of the resulting client.
+    ///
+    /// If `None`, the client waits for responses indefinitely.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Connect for Connection {
+    type Client = Client;
+
+    /// Resolve the address of the given server.
+    ///
+    /// Since connecting a UDP socket does not contact the server,
+    /// the resolved addresses are probed in turn when logging in on the connection.
+    fn connect<T>(address: T) -> Result<Self, Error>
+    where
+        T: ToSocketAddrs,
+    {
+        Ok(Self {
+            target: Target::Addresses(resolve(address)?),
+            timeout: Some(DEFAULT_TIMEOUT),
+        })
+    }
+
+    /// Perform a login, consuming the connection.
+    ///
+    /// Since UDP sockets connect without contacting the server,
+    /// each resolved address is probed with the login in turn,
+    /// until a server responds within the connection's default timeout.
+    fn login<T>(self, password: T) -> Result<Self::Client, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        let password = password.as_ref();
+
+        match self.target {
+            Target::Transport(transport) => login(transport, self.timeout, password),
+            Target::Addresses(addresses) => connect_any(addresses.as_slice(), |address| {
+                let udp_socket = UdpSocket::bind(SocketAddr::new(unspecified(address), 0))?;
+                udp_socket.connect(address)?;
+                login(Transport::new(udp_socket), self.timeout, password)
+            }),
+        }?
+        .ok_or(Error::AuthenticationFailed)
+    }
+}
+
+impl From<UdpSocket> for Connection {
+    fn from(udp_socket: UdpSocket) -> Self {
+        Self::new(udp_socket)
+    }
+}
+
+/// The server to log in to.
+#[derive(Debug)]
+enum Target {
+    /// An already connected UDP socket.
+    Transport(Transport),
+    /// The resolved addresses of the server, each of which is probed with the login in turn.
+    Addresses(Vec<SocketAddr>),
+}
+
+/// Log in on the given transport.
+///
+/// Returns `None` if the server rejected the password.
+fn login(
+    mut transport: Transport,
+    timeout: Option<Duration>,
+    password: &[u8],
+) -> Result<Option<Client>, Error> {
+    transport.timeout = timeout;
+    let deadline = Deadline::after(timeout);
+    let bytes: Box<[u8]> = login::Request::from(password).into();
+    transport.send(&bytes, deadline)?;
+
+    loop {
+        match transport.receive(deadline)? {
+            Some(Response::Login(response)) => {
+                return Ok(response.success().then(|| Client::new(transport)));
+            }
+            Some(Response::Command(response)) => {
+                warn!("Discarding response #{} before login", response.seq());
+            }
+            Some(Response::Message(_)) | None => (),
+        }
+    }
+}
+
+/// An authenticated blocking `BattlEye Rcon` client.
+///
+/// Obtain a client by logging in on a [`Connection`].
+///
+/// Server messages are acknowledged while waiting for responses, but not retained.
+/// Since the client does not send keepalive packets in the background,
+/// the server drops the session if no command is sent for 45 seconds.
+#[derive(Debug)]
+pub struct Client {
+    transport: Transport,
+    seq: u8,
+}
+
+impl Client {
+    const fn new(transport: Transport) -> Self {
+        Self { transport, seq: 0 }
+    }
+
+    /// Returns the default timeout for commands.
+    #[must_use]
+    pub const fn timeout(&self) -> Option<Duration> {
+        self.transport.timeout
+    }
+
+    /// Set the default timeout for commands.
+    ///
+    /// If `None`, the client waits for responses indefinitely.
+    pub const fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.transport.timeout = timeout;
+    }
+
+    fn run_inner(&mut self, command: &[u8], deadline: Deadline) -> Result<Vec<u8>, Error> {
+        let seq = self.seq;
+        self.seq = self.seq.wrapping_add(1);
+        let bytes: Box<[u8]> = command::Request::command(seq, command).into();
+        self.transport.send(&bytes, deadline)?;
+        let mut reassembly = Reassembly::default();
+
+        loop {
+            match self.transport.receive(deadline)? {
+                Some(Response::Command(response)) if response.seq() == seq => {
+                    if let Some(bytes) = reassembly.push(&response)? {
+                        return Ok(bytes);
+                    }
+                }
+                Some(Response::Command(response)) => {
+                    debug!("Discarding late response #{}", response.seq());
+                }
+                Some(Response::Login(_)) => warn!("Discarding unexpected login response"),
+                Some(Response::Message(_)) | None => (),
+            }
+        }
+    }
+}
+
+impl RCon for Client {
+    fn run<T>(&mut self, command: T) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.run_inner(command.as_ref(), Deadline::after(self.transport.timeout))
+    }
+
+    fn run_with_timeout<T>(&mut self, command: T, timeout: Duration) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.run_inner(command.as_ref(), Deadline::after(Some(timeout)))
+    }
+}
+
+/// A connected UDP socket alongside its receive buffer.
+#[derive(Debug)]
+struct Transport {
+    udp_socket: UdpSocket,
+    timeout: Option<Duration>,
+    buffer: Box<[u8]>,
+}
+
+impl Transport {
+    fn new(udp_socket: UdpSocket) -> Self {
+        Self {
+            udp_socket,
+            timeout: Some(DEFAULT_TIMEOUT),
+            buffer: vec![0; BUF_SIZE].into_boxed_slice(),
+        }
+    }
+
+    fn send(&self, bytes: &[u8], deadline: Deadline) -> Result<(), Error> {
+        trace!("Sending bytes: {bytes:#04X?}");
+        self.udp_socket.set_write_timeout(deadline.remaining()?)?;
+        self.udp_socket
+            .send(bytes)
+            .map(drop)
+            .map_err(|error| timed_out(Error::Io(error)))
+    }
+
+    /// Receive the next datagram.
+    ///
+    /// Server messages are acknowledged and yield `None`.
+    /// Malformed datagrams are discarded and yield `None`, since anyone can send junk to a UDP socket.
+    fn receive(&mut self, deadline: Deadline) -> Result<Option<Response>, Error> {
+        self.udp_socket.set_read_timeout(deadline.remaining()?)?;
+        let size = self
+            .udp_socket
+            .recv(&mut self.buffer)
+            .map_err(|error| timed_out(Error::Io(error)))?;
+        trace!("Received {size} bytes");
+
+        let response = match Response::decode(&self.buffer[..size.min(self.buffer.len())]) {
+            Ok(response) => response,
+            Err(error) => {
+                warn!("Discarding incoming datagram: {error}");
+                trace!("Error: {error:?}");
+                return Ok(None);
+            }
+        };
+
+        match response {
+            Response::Message(message) => {
+                debug!("Ack'ing message #{}", message.seq());
+                trace!("Message: {message:?}");
+                self.send(&<[u8; 9]>::from(Ack::new(message.seq())), deadline)?;
+                Ok(None)
+            }
+            response => Ok(Some(response)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{SocketAddr, UdpSocket};
+    use std::thread::{JoinHandle, spawn};
+    use std::time::Duration;
+
+    use super::{Client, Connection};
+    use crate::Error;
+    use crate::battleye::packet::server::Message;
+    use crate::battleye::packet::{Request, Response, command, login};
+    use crate::blocking::{Connect, RCon};
+
+    /// Spawn a fake server on a background thread.
+    fn spawn_server<F>(serve: F) -> (SocketAddr, JoinHandle<()>)
+    where
+        F: FnOnce(&UdpSocket) + Send + 'static,
+    {
+        let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = server.local_addr().unwrap();
+        (address, spawn(move || serve(&server)))
+    }
+
+    /// Receive the next request sent to the fake server.
+    fn receive(server: &UdpSocket) -> (Request, SocketAddr) {
+        let mut buffer = [0; 512];
+        let (size, client) = server.recv_from(&mut buffer).unwrap();
+        (Request::decode(&buffer[..size]).unwrap(), client)
+    }
+
+    /// Send a response from the fake server.
+    fn send(server: &UdpSocket, response: Response, client: SocketAddr) {
+        let datagram: Box<[u8]> = response.into();
+        server.send_to(&datagram, client).unwrap();
+    }
+
+    /// Answer the login request with the given verdict.
+    fn accept_login(server: &UdpSocket, success: bool) -> SocketAddr {
+        let (request, client) = receive(server);
+        assert!(matches!(request, Request::Login(_)));
+        send(
+            server,
+            Response::Login(login::Response::new(success)),
+            client,
+        );
+        client
+    }
+
+    /// Receive the next command request sent to the fake server.
+    fn receive_command(server: &UdpSocket) -> (command::Request, SocketAddr) {
+        match receive(server) {
+            (Request::Command(request), client) => (request, client),
+            (request, _) => panic!("Expected command request, got {request:?}"),
+        }
+    }
+
+    fn login(address: SocketAddr) -> Result<Client, Error> {
+        Connection::connect(address)?
+            .with_timeout(Some(Duration::from_secs(5)))
+            .login("secret")
+    }
+
+    #[test]
+    fn test_login_and_run() {
+        let (address, handle) = spawn_server(|server| {
+            accept_login(server, true);
+            let (request, client) = receive_command(server);
+            assert_eq!(request.payload(), b"players");
+            send(
+                server,
+                Response::Command(command::Response::new(request.seq(), b"Players:".to_vec())),
+                client,
+            );
+        });
+
+        let mut client = login(address).unwrap();
+        assert_eq!(client.run("players").unwrap(), b"Players:");
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_login_rejected() {
+        let (address, handle) = spawn_server(|server| {
+            accept_login(server, false);
+        });
+
+        assert!(matches!(login(address), Err(Error::AuthenticationFailed)));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_reassemble_multipart_response() {
+        let (address, handle) = spawn_server(|server| {
+            accept_login(server, true);
+            let (request, client) = receive_command(server);
+
+            for (index, part) in [(1, b"World".as_slice()), (0, b"Hello, ")] {
+                send(
+                    server,
+                    Response::Command(command::Response::new(
+                        request.seq(),
+                        [&[0x00, 2, index], part].concat(),
+                    )),
+                    client,
+                );
+            }
+        });
+
+        let mut client = login(address).unwrap();
+        assert_eq!(client.run("players").unwrap(), b"Hello, World");
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_ack_server_message_while_waiting() {
+        let (address, handle) = spawn_server(|server| {
+            accept_login(server, true);
+            let (request, client) = receive_command(server);
+            send(
+                server,
+                Response::Message(Message::new(7, b"Hello".to_vec())),
+                client,
+            );
+
+            let (Request::Ack(ack), _) = receive(server) else {
+                panic!("Expected acknowledgement of the server message");
+            };
+            assert_eq!(ack.seq(), 7);
+            send(
+                server,
+                Response::Command(command::Response::new(request.seq(), b"pong".to_vec())),
+                client,
+            );
+        });
+
+        let mut client = login(address).unwrap();
+        assert_eq!(client.run("ping").unwrap(), b"pong");
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_discard_malformed_datagram() {
+        let (address, handle) = spawn_server(|server| {
+            accept_login(server, true);
+            let (request, client) = receive_command(server);
+            let response: Box<[u8]> =
+                Response::Command(command::Response::new(request.seq(), b"pong".to_vec())).into();
+            let mut corrupt = response.to_vec();
+            corrupt[2] ^= 0xFF;
+            server.send_to(&corrupt, client).unwrap();
+            server.send_to(&response, client).unwrap();
+        });
+
+        let mut client = login(address).unwrap();
+        assert_eq!(client.run("ping").unwrap(), b"pong");
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_run_with_timeout() {
+        let (address, handle) = spawn_server(|server| {
+            accept_login(server, true);
+            receive_command(server);
+        });
+
+        let mut client = login(address).unwrap();
+        assert!(matches!(
+            client.run_with_timeout("players", Duration::from_millis(100)),
+            Err(Error::TimedOut)
+        ));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_login_probes_addresses() {
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let (server, handle) = spawn_server(|server| {
+            accept_login(server, true);
+        });
+
+        Connection::connect([silent.local_addr().unwrap(), server].as_slice())
+            .unwrap()
+            .with_timeout(Some(Duration::from_millis(100)))
+            .login("secret")
+            .unwrap();
+        handle.join().unwrap();
+    }
+}
@@ -1,171 +1,233 @@
-use std::io::{Error, ErrorKind};
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::io::ErrorKind;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::sync::atomic::AtomicU8;
 use std::sync::atomic::Ordering::SeqCst;
-use std::sync::atomic::{AtomicBool, AtomicU8};
 use std::time::Duration;
 
-use log::{debug, trace};
+use log::{trace, warn};
+use tokio::net::UdpSocket;
 use tokio::spawn;
-use tokio::sync::mpsc::{Receiver, Sender, channel};
-use tokio::task::JoinHandle;
-
-use crate::RCon;
-use crate::battleye::client::handler::Handler;
-use crate::battleye::packet::{CommunicationResult, Request, Response, command, login};
-
-mod handler;
-
-const DEFAULT_CHANNEL_SIZE: usize = 8;
-const DEFAULT_BUF_SIZE: usize = 1024;
-const DEFAULT_SOCKET_TIMEOUT: Option<Duration> = Some(Duration::from_millis(100));
-
-/// A `BattlEye Rcon` client.
-#[derive(Debug)]
+use tokio::sync::mpsc::{Sender, channel};
+use tokio::sync::{broadcast, oneshot, watch};
+
+pub use self::builder::ClientBuilder;
+use self::settings::Settings;
+use crate::battleye::client::handler::{Handler, IDLE_TIMEOUT, Transaction};
+use crate::battleye::packet::{CommunicationResult, Request, command, login};
+use crate::battleye::{ServerMessage, SessionEvent};
+use crate::timeout::timeout;
+use crate::{Error, RCon, RetryPolicy};
+
+mod builder;
+pub(super) mod handler;
+pub(super) mod reassembly;
+mod settings;
+
+/// An authenticated `BattlEye Rcon` client.
+///
+/// Obtain a client by logging in on a [`Connection`](super::Connection).
+/// The client can be cloned cheaply to issue commands from several tasks concurrently.
+/// All clones share the same UDP session.
+/// Responses are routed to the respective caller by the command's sequence number.
+///
+/// The session is closed once all clones of the client have been dropped.
+#[derive(Clone, Debug)]
 pub struct Client {
-    running: Arc<AtomicBool>,
-    requests: Sender<Request>,
-    responses: Receiver<std::io::Result<Response>>,
-    handler: Option<JoinHandle<()>>,
-    buffer: Vec<command::Response>,
+    requests: Sender<Transaction>,
+    messages: broadcast::Sender<ServerMessage>,
+    events: broadcast::Sender<SessionEvent>,
+    settings: Arc<watch::Sender<Settings>>,
     seq: Arc<AtomicU8>,
+    timeout: Option<Duration>,
+    peer_addr: Option<SocketAddr>,
 }
 
 impl Client {
-    /// Creates a new instance of the client.
+    /// Returns a builder to configure a new connection.
     #[must_use]
-    pub fn new(udp_socket: UdpSocket, channel_size: usize, buf_size: usize) -> Self {
-        let running = Arc::new(AtomicBool::new(true));
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    fn with_settings(
+        udp_socket: UdpSocket,
+        channel_size: usize,
+        buf_size: usize,
+        timeout: Option<Duration>,
+        settings: Settings,
+    ) -> Self {
         let (requests_tx, requests_rx) = channel(channel_size);
-        let (response_tx, response_rx) = channel(channel_size);
+        let (messages, _) = broadcast::channel(channel_size);
+        let (events, _) = broadcast::channel(channel_size);
+        let (settings_tx, settings_rx) = watch::channel(settings);
         let seq = Arc::new(AtomicU8::new(0));
+        let peer_addr = udp_socket.peer_addr().ok();
         let handler = Handler::new(
             udp_socket,
             seq.clone(),
-            running.clone(),
             requests_rx,
-            response_tx,
+            messages.clone(),
+            events.clone(),
+            settings_rx,
             buf_size,
         );
-        let join_handle = spawn(handler.run());
+        spawn(handler.run());
         Self {
-            running,
             requests: requests_tx,
-            responses: response_rx,
-            handler: Some(join_handle),
-            buffer: Vec::new(),
+            messages,
+            events,
+            settings: Arc::new(settings_tx),
             seq,
+            timeout,
+            peer_addr,
         }
     }
 
-    async fn communicate(&mut self, request: Request) -> std::io::Result<CommunicationResult> {
-        trace!("Sending request {request:?}");
-        self.requests
-            .send(request)
-            .await
-            .map_err(|_| Error::new(ErrorKind::BrokenPipe, "Failed to send request to handler"))?;
-
-        debug!("Clearing buffer");
-        self.buffer.clear();
-
-        loop {
-            debug!("Receiving response");
-            match self.responses.recv().await {
-                Some(response) => match response? {
-                    Response::Command(response) => {
-                        debug!("Received command response");
-                        trace!("Received response {response:?}");
-                        let seq = response.seq() as usize;
-                        self.buffer.push(response);
-
-                        if self.buffer.len() >= seq {
-                            debug!("Buffer size exceeds sequence number. Returning.");
-                            trace!("Buffer size: {}", self.buffer.len());
-                            trace!("Sequence number: {seq}");
-                            return Ok(CommunicationResult::Command(self.collect_responses()));
-                        }
-                    }
-                    Response::Login(response) => {
-                        debug!("Received login response. Returning.");
-                        trace!("Login response {response:?}");
-                        return Ok(CommunicationResult::Login(response));
-                    }
-                },
-                None => return Err(ErrorKind::BrokenPipe.into()),
-            }
-        }
+    /// Returns the address of the server the client is connected to.
+    ///
+    /// If the server address resolved to several socket addresses,
+    /// this is the one that responded to the login.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the client was built on a UDP socket that was not connected.
+    pub fn peer_addr(&self) -> Result<SocketAddr, Error> {
+        self.peer_addr.ok_or_else(|| {
+            Error::Io(std::io::Error::new(
+                ErrorKind::NotConnected,
+                "UDP socket is not connected",
+            ))
+        })
     }
 
-    fn collect_responses(&mut self) -> Vec<u8> {
-        self.buffer.sort_by_key(command::Response::seq);
-        self.buffer
-            .iter()
-            .flat_map(command::Response::payload)
-            .copied()
-            .collect()
+    /// Returns the default timeout for commands.
+    #[must_use]
+    pub const fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Set the default timeout for commands.
+    ///
+    /// If `None`, the client waits for responses indefinitely.
+    /// The setting applies to this client and clones created from it afterwards.
+    pub const fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
     }
-}
 
-impl Drop for Client {
-    fn drop(&mut self) {
-        self.running.store(false, SeqCst);
+    /// Returns the interval of inactivity after which a keepalive packet is sent.
+    #[must_use]
+    pub fn keepalive_interval(&self) -> Duration {
+        self.settings.borrow().keepalive_interval
+    }
 
-        if let Some(handler) = self.handler.take() {
-            handler.abort();
+    /// Set the interval of inactivity after which a keepalive packet is sent.
+    ///
+    /// The server drops clients that did not send any packets for 45 seconds,
+    /// so the interval should be well below that.
+    /// The setting applies to all clones of the client.
+    pub fn set_keepalive_interval(&self, interval: Duration) {
+        if interval >= IDLE_TIMEOUT {
+            warn!("Keepalive interval {interval:?} exceeds the server's idle timeout");
         }
+
+        self.settings
+            .send_modify(|settings| settings.keepalive_interval = interval);
     }
-}
 
-impl RCon for Client {
-    async fn connect<T>(address: T) -> std::io::Result<Self>
-    where
-        T: Into<SocketAddr> + Send,
-    {
-        let address = address.into();
-        let socket = UdpSocket::bind(if address.is_ipv4() {
-            SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)
-        } else {
-            SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0)
-        })?;
+    /// Returns the policy for reconnecting after the session has been lost.
+    #[must_use]
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.settings.borrow().retry_policy
+    }
 
-        socket.set_read_timeout(DEFAULT_SOCKET_TIMEOUT)?;
-        socket.connect(address)?;
+    /// Set the policy for reconnecting after the session has been lost.
+    ///
+    /// The setting applies to all clones of the client.
+    pub fn set_retry_policy(&self, retry_policy: RetryPolicy) {
+        self.settings
+            .send_modify(|settings| settings.retry_policy = retry_policy);
+    }
 
-        Ok(Self::new(socket, DEFAULT_CHANNEL_SIZE, DEFAULT_BUF_SIZE))
+    /// Subscribe to messages sent by the server.
+    ///
+    /// The returned receiver yields every server message received after subscribing.
+    /// Receivers that fall behind by more than the client's channel size will
+    /// skip the oldest messages, see [`broadcast::Receiver::recv`].
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<ServerMessage> {
+        self.messages.subscribe()
     }
 
-    async fn login<T>(&mut self, password: T) -> std::io::Result<bool>
-    where
-        T: AsRef<[u8]> + Send,
-    {
-        match self
-            .communicate(Request::Login(login::Request::from(password.as_ref())))
-            .await?
+    /// Subscribe to events concerning the session to the server.
+    ///
+    /// The client detects a lost session, e.g. after a server restart,
+    /// by unacknowledged keepalive packets or ICMP errors.
+    /// If it was logged in, it then reconnects to the server and logs in again
+    /// according to its [`RetryPolicy`], emitting a [`SessionEvent`] on each state change.
+    #[must_use]
+    pub fn session_events(&self) -> broadcast::Receiver<SessionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Perform a login.
+    ///
+    /// Returns `true` if the server accepted the password.
+    pub(super) async fn login(&self, password: &[u8]) -> Result<bool, Error> {
+        match timeout(
+            self.timeout,
+            self.communicate(Request::Login(login::Request::from(password))),
+        )
+        .await?
         {
             CommunicationResult::Login(response) => Ok(response.success()),
-            CommunicationResult::Command(_) => Err(Error::new(
-                ErrorKind::InvalidData,
-                "Expected login response, but got a command response.",
-            )),
+            CommunicationResult::Command(_) => Err(Error::UnexpectedResponse),
         }
     }
 
-    async fn run<T>(&mut self, command: T) -> std::io::Result<Vec<u8>>
-    where
-        T: AsRef<[u8]> + Send,
-    {
+    async fn execute(&self, command: &[u8]) -> Result<Vec<u8>, Error> {
         match self
             .communicate(Request::Command(command::Request::command(
                 self.seq.fetch_add(1, SeqCst),
-                command.as_ref(),
+                command,
             )))
             .await?
         {
             CommunicationResult::Command(bytes) => Ok(bytes),
-            CommunicationResult::Login(_) => Err(Error::new(
-                ErrorKind::InvalidData,
-                "Expected command response, but got a login response.",
-            )),
+            CommunicationResult::Login(_) => Err(Error::UnexpectedResponse),
         }
     }
+
+    async fn communicate(&self, request: Request) -> Result<CommunicationResult, Error> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        trace!("Sending request {request:?}");
+        // The handler only stops after the session has been lost for good.
+        self.requests
+            .send((request, response_tx))
+            .await
+            .map_err(|_| Error::Disconnected)?;
+
+        response_rx.await.map_err(|_| Error::Disconnected)?
+    }
+}
+
+impl RCon for Client {
+    async fn run<T>(&mut self, command: T) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]> + Send,
+    {
+        timeout(self.timeout, self.execute(command.as_ref())).await
+    }
+
+    async fn run_with_timeout<T>(
+        &mut self,
+        command: T,
+        duration: Duration,
+    ) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]> + Send,
+    {
+        timeout(Some(duration), self.execute(command.as_ref())).await
+    }
 }
@@ -0,0 +1,175 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use log::warn;
+use tokio::net::{ToSocketAddrs, UdpSocket};
+
+use super::Client;
+use super::handler::{IDLE_TIMEOUT, unspecified};
+use super::settings::Settings;
+use crate::battleye::Connection;
+use crate::resolve::resolve;
+use crate::timeout::timeout;
+use crate::{Error, RetryPolicy};
+
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_CHANNEL_SIZE: usize = 8;
+/// Maximum payload size of a UDP datagram, so that parts of large responses are never truncated.
+const DEFAULT_BUF_SIZE: usize = 65_507;
+const DEFAULT_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Builder for a `BattlEye Rcon` [`Connection`] with custom settings.
+///
+/// Log in on the connection to obtain a [`Client`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ClientBuilder {
+    bind_address: Option<SocketAddr>,
+    connect_timeout: Duration,
+    timeout: Option<Duration>,
+    channel_size: usize,
+    buf_size: usize,
+    keepalive_interval: Duration,
+    retry_policy: RetryPolicy,
+}
+
+impl ClientBuilder {
+    /// Creates a new builder with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            bind_address: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            timeout: Some(DEFAULT_TIMEOUT),
+            channel_size: DEFAULT_CHANNEL_SIZE,
+            buf_size: DEFAULT_BUF_SIZE,
+            keepalive_interval: DEFAULT_KEEPALIVE_INTERVAL,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+
+    /// Set the local address to bind the UDP socket to.
+    ///
+    /// Defaults to an ephemeral port on the unspecified address of the server address' family.
+    /// When reconnecting, the client binds to the same IP address on an ephemeral port.
+    #[must_use]
+    pub const fn bind_address(mut self, bind_address: SocketAddr) -> Self {
+        self.bind_address = Some(bind_address);
+        self
+    }
+
+    /// Set the timeout for establishing the session.
+    ///
+    /// This limits the time to wait for the login response from each resolved address
+    /// of the server when logging in, and when reconnecting.
+    #[must_use]
+    pub const fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Set the default timeout for the login and commands.
+    ///
+    /// If `None`, the client waits for responses indefinitely.
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the capacity of the channels for requests, server messages and session events.
+    #[must_use]
+    pub const fn channel_size(mut self, channel_size: usize) -> Self {
+        self.channel_size = channel_size;
+        self
+    }
+
+    /// Set the size of the receive buffer.
+    ///
+    /// Datagrams exceeding this size are truncated.
+    #[must_use]
+    pub const fn buf_size(mut self, buf_size: usize) -> Self {
+        self.buf_size = buf_size;
+        self
+    }
+
+    /// Set the interval of inactivity after which a keepalive packet is sent.
+    #[must_use]
+    pub fn keepalive_interval(mut self, keepalive_interval: Duration) -> Self {
+        if keepalive_interval >= IDLE_TIMEOUT {
+            warn!("Keepalive interval {keepalive_interval:?} exceeds the server's idle timeout");
+        }
+
+        self.keepalive_interval = keepalive_interval;
+        self
+    }
+
+    /// Set the policy for reconnecting after the session has been lost.
+    #[must_use]
+    pub const fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Resolve the address of the given server.
+    ///
+    /// Since connecting a UDP socket does not contact the server,
+    /// the resolved addresses are probed in turn when logging in on the connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the address cannot be resolved.
+    pub async fn connect<T>(self, address: T) -> Result<Connection, Error>
+    where
+        T: ToSocketAddrs,
+    {
+        let addresses = timeout(Some(self.connect_timeout), resolve(address)).await?;
+        Ok(Connection::from_addresses(self, addresses))
+    }
+
+    /// Build a connection on an already connected UDP socket.
+    #[must_use]
+    pub fn build(self, udp_socket: UdpSocket) -> Connection {
+        Connection::from_client(self.client(udp_socket))
+    }
+
+    /// Log in on the given server address.
+    ///
+    /// Returns `None` if the server rejected the password.
+    pub(in crate::battleye) async fn probe(
+        self,
+        address: SocketAddr,
+        password: &[u8],
+    ) -> Result<Option<Client>, Error> {
+        let udp_socket = UdpSocket::bind(
+            self.bind_address
+                .unwrap_or_else(|| SocketAddr::new(unspecified(address), 0)),
+        )
+        .await?;
+        udp_socket.connect(address).await?;
+        let client = self.client(udp_socket);
+        let accepted = timeout(Some(self.connect_timeout), client.login(password)).await?;
+        Ok(accepted.then_some(client))
+    }
+
+    fn client(self, udp_socket: UdpSocket) -> Client {
+        Client::with_settings(
+            udp_socket,
+            self.channel_size,
+            self.buf_size,
+            self.timeout,
+            Settings {
+                keepalive_interval: self.keepalive_interval,
+                retry_policy: self.retry_policy,
+                connect_timeout: self.connect_timeout,
+                bind_address: self.bind_address.map(|address| address.ip()),
+            },
+        )
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,31 +1,61 @@
-use std::error::Error;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::io::ErrorKind;
-use std::net::UdpSocket;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::sync::Arc;
+use std::sync::atomic::AtomicU8;
 use std::sync::atomic::Ordering::SeqCst;
-use std::sync::atomic::{AtomicBool, AtomicU8};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 
 use log::{debug, error, trace, warn};
-use tokio::sync::mpsc::error::TryRecvError;
-use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::net::UdpSocket;
+use tokio::select;
+use tokio::sync::mpsc::Receiver;
+use tokio::sync::{broadcast, oneshot, watch};
+use tokio::time::{MissedTickBehavior, interval, sleep, timeout};
 
-use crate::battleye::from_server::FromServer;
-use crate::battleye::header::Header;
+use self::seq_window::SeqWindow;
+use super::reassembly::Reassembly;
+use super::settings::Settings;
+use crate::Error;
 use crate::battleye::packet::server::{Ack, Message};
-use crate::battleye::packet::{Request, Response, command, login, server};
+use crate::battleye::packet::{CommunicationResult, Request, Response, command, login};
+use crate::battleye::{ServerMessage, SessionEvent};
+
+mod seq_window;
 
 /// Idle timeout according to protocol definition: <https://www.battleye.com/downloads/BERConProtocol.txt>
-const IDLE_TIMEOUT: Duration = Duration::from_secs(45);
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(45);
+/// Time to wait for the next part of a multipart response before giving up.
+const MULTIPART_TIMEOUT: Duration = Duration::from_secs(5);
+/// Interval in which to check for expired responses and the need to send keepalive packets.
+const HOUSEKEEPING_INTERVAL: Duration = Duration::from_secs(1);
+/// Amount of consecutive unacknowledged keepalive packets after which the session is considered lost.
+const MAX_MISSED_KEEPALIVES: u32 = 2;
+
+/// A channel to send the result of a request back to the caller.
+pub type Waiter = oneshot::Sender<Result<CommunicationResult, Error>>;
+
+/// A request alongside the channel to send its result to.
+pub type Transaction = (Request, Waiter);
 
 #[derive(Debug)]
 pub struct Handler {
     udp_socket: UdpSocket,
     seq: Arc<AtomicU8>,
-    running: Arc<AtomicBool>,
-    requests: Receiver<Request>,
-    responses: Sender<std::io::Result<Response>>,
-    last_command: Option<SystemTime>,
+    requests: Receiver<Transaction>,
+    messages: broadcast::Sender<ServerMessage>,
+    events: broadcast::Sender<SessionEvent>,
+    settings: watch::Receiver<Settings>,
+    last_sent: Instant,
+    keepalive: Option<u8>,
+    missed_keepalives: u32,
+    credentials: Option<login::Request>,
+    pending_login: Option<login::Request>,
+    login: Option<Waiter>,
+    commands: HashMap<u8, Waiter>,
+    reassembly: Reassembly,
+    seen: SeqWindow,
     buffer: Box<[u8]>,
 }
 
@@ -34,183 +64,523 @@ impl Handler {
     pub fn new(
         udp_socket: UdpSocket,
         seq: Arc<AtomicU8>,
-        running: Arc<AtomicBool>,
-        requests: Receiver<Request>,
-        responses: Sender<std::io::Result<Response>>,
+        requests: Receiver<Transaction>,
+        messages: broadcast::Sender<ServerMessage>,
+        events: broadcast::Sender<SessionEvent>,
+        settings: watch::Receiver<Settings>,
         buf_size: usize,
     ) -> Self {
         Self {
             udp_socket,
             seq,
-            running,
             requests,
-            responses,
-            last_command: None,
+            messages,
+            events,
+            settings,
+            last_sent: Instant::now(),
+            keepalive: None,
+            missed_keepalives: 0,
+            credentials: None,
+            pending_login: None,
+            login: None,
+            commands: HashMap::new(),
+            reassembly: Reassembly::default(),
+            seen: SeqWindow::new(),
             buffer: vec![0; buf_size].into_boxed_slice(),
         }
     }
 
     pub async fn run(mut self) {
-        while self.running.load(SeqCst) {
-            trace!("Receiving request");
-            match self.requests.try_recv() {
-                Ok(request) => {
+        let mut housekeeping = interval(HOUSEKEEPING_INTERVAL);
+        housekeeping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            let alive = select! {
+                request = self.requests.recv() => {
+                    let Some((request, waiter)) = request else {
+                        debug!("Request channel disconnected");
+                        return;
+                    };
+
                     trace!("Received request: {request:?}");
-                    self.handle_request(request);
+                    self.handle_request(request, waiter).await;
+                    true
                 }
-                Err(error) => match error {
-                    TryRecvError::Disconnected => {
-                        error!("Request channel disconnected");
-                        return;
-                    }
-                    TryRecvError::Empty => {
-                        self.process_incoming_messages().await;
-                        self.keepalive();
-                    }
-                },
+                result = self.udp_socket.recv(&mut self.buffer) => {
+                    self.process_incoming_message(result).await
+                }
+                _ = housekeeping.tick() => {
+                    self.expire_responses();
+                    self.keepalive().await
+                }
+            };
+
+            if !alive && !self.recover().await {
+                return;
             }
         }
     }
 
-    fn handle_request(&self, request: Request) {
+    async fn handle_request(&mut self, request: Request, waiter: Waiter) {
         trace!("Handling request: {request:?}");
 
-        if let Err(error) = self.send(request) {
+        let seq = match &request {
+            Request::Command(command) => Some(command.seq()),
+            Request::Login(_) => None,
+            Request::Ack(_) => {
+                reply(
+                    waiter,
+                    Err(Error::Io(std::io::Error::new(
+                        ErrorKind::InvalidInput,
+                        "Acknowledgements are sent by the handler",
+                    ))),
+                );
+                return;
+            }
+        };
+
+        if let Some(seq) = seq {
+            let Entry::Vacant(entry) = self.commands.entry(seq) else {
+                reply(
+                    waiter,
+                    Err(Error::Io(std::io::Error::new(
+                        ErrorKind::ResourceBusy,
+                        format!("Sequence number #{seq} is already in use"),
+                    ))),
+                );
+                return;
+            };
+
+            entry.insert(waiter);
+        } else if let Some(previous) = self.login.replace(waiter) {
+            reply(
+                previous,
+                Err(Error::Io(std::io::Error::new(
+                    ErrorKind::Interrupted,
+                    "Login superseded by another login request",
+                ))),
+            );
+        }
+
+        if let Request::Login(login) = &request {
+            self.pending_login = Some(login.clone());
+        }
+
+        if let Err(error) = self.send(request).await {
             error!("{error}");
+
+            let waiter = match seq {
+                Some(seq) => self.remove_command(seq),
+                None => self.login.take(),
+            };
+
+            if let Some(waiter) = waiter {
+                reply(waiter, Err(Error::Io(error)));
+            }
         }
     }
 
-    fn send(&self, request: Request) -> std::io::Result<usize> {
+    async fn send(&mut self, request: Request) -> std::io::Result<usize> {
         trace!("Sending request: {request:?}");
+        self.last_sent = Instant::now();
+        let bytes: Box<[u8]> = request.into();
+        trace!("Sending bytes: {bytes:#04X?}");
+        self.udp_socket.send(&bytes).await
+    }
 
-        match request {
-            Request::Command(request) => {
-                let bytes: Box<[u8]> = request.into();
-                trace!("Sending bytes: {bytes:#04X?}");
-                self.udp_socket.send(&bytes)
+    /// Process an incoming message.
+    ///
+    /// Malformed datagrams are discarded, since anyone can send junk to a UDP socket.
+    /// Returns `false` if the session has been lost.
+    async fn process_incoming_message(&mut self, result: std::io::Result<usize>) -> bool {
+        debug!("Processing incoming message");
+
+        match self.receive_response(result) {
+            Ok(response) => {
+                self.dispatch(response).await;
+                true
             }
-            Request::Login(request) => {
-                let bytes: Box<[u8]> = request.into();
-                trace!("Sending bytes: {bytes:#04X?}");
-                self.udp_socket.send(&bytes)
+            Err(error) if is_session_loss(&error) => {
+                error!("Session lost while receiving: {error}");
+                self.fail_all(error);
+                false
+            }
+            Err(error) => {
+                warn!("Discarding incoming datagram: {error}");
+                trace!("Error: {error:?}");
+                true
             }
         }
     }
 
-    async fn process_incoming_messages(&mut self) {
-        debug!("Processing incoming messages");
+    fn receive_response(&self, result: std::io::Result<usize>) -> Result<Response, Error> {
+        let bytes = result?;
+        trace!("Received {bytes} bytes");
+        Response::decode(&self.buffer[..bytes.min(self.buffer.len())])
+    }
 
-        match self.receive_response() {
-            Ok(result) => {
-                if let Some(response) = result {
-                    self.forward(Ok(response)).await;
+    async fn dispatch(&mut self, response: Response) {
+        debug!("Dispatching response from UDP stream");
+        trace!("Response: {response:?}");
+
+        match response {
+            Response::Command(response) => {
+                let seq = response.seq();
+
+                if self.keepalive == Some(seq) {
+                    debug!("Server acknowledged keepalive #{seq}");
+                    self.keepalive = None;
+                    self.missed_keepalives = 0;
+                    return;
+                }
+
+                if !self.commands.contains_key(&seq) {
+                    warn!("Discarding response #{seq} without pending request");
+                    return;
+                }
+
+                let result = match self.reassembly.push(&response) {
+                    Ok(Some(bytes)) => Ok(CommunicationResult::Command(bytes)),
+                    Ok(None) => return,
+                    Err(error) => Err(error),
+                };
+
+                if let Some(waiter) = self.remove_command(seq) {
+                    reply(waiter, result);
                 }
             }
-            Err(error) => {
-                debug!("Error while processing incoming messages: {error}");
-                trace!("Error kind: {:?}", error.kind());
-                trace!("Error source: {:?}", error.source());
+            Response::Login(response) => {
+                if response.success() {
+                    self.credentials = self.pending_login.take();
+                } else {
+                    self.pending_login = None;
+                }
+
+                if let Some(waiter) = self.login.take() {
+                    reply(waiter, Ok(CommunicationResult::Login(response)));
+                } else {
+                    warn!("Discarding login response without pending request");
+                }
+            }
+            Response::Message(message) => {
+                debug!("Received server message");
+                self.ack(&message).await;
 
-                if error.kind() != ErrorKind::TimedOut && error.kind() != ErrorKind::WouldBlock {
-                    self.forward(Err(error)).await;
+                if self.seen.insert(message.seq()) {
+                    self.publish(&message);
+                } else {
+                    debug!("Discarding retransmitted message #{}", message.seq());
                 }
             }
         }
     }
 
-    fn receive_response(&mut self) -> std::io::Result<Option<Response>> {
-        debug!("Receiving packet from UDP socket");
-        let bytes = self.udp_socket.recv(&mut self.buffer)?;
-        trace!("Received {bytes} bytes");
+    fn expire_responses(&mut self) {
+        // Free the sequence numbers of commands whose callers gave up, e.g. due to a timeout.
+        let reassembly = &mut self.reassembly;
+        self.commands.retain(|&seq, waiter| {
+            let closed = waiter.is_closed();
 
-        trace!("Setting up byte stream");
-        let mut stream = self.buffer.iter().take(bytes).copied();
-
-        debug!("Parsing header from buffer");
-        let header = Header::read_from(&mut stream)?;
-        trace!("Received header: {header:?}");
-
-        match header.typ() {
-            command::TYPE => {
-                debug!("Received command response");
-                return command::Response::read_from(&mut stream)
-                    .map(|f| f(header))
-                    .and_then(FromServer::validate)
-                    .map(Response::Command)
-                    .map(Some);
-            }
-            login::TYPE => {
-                debug!("Received login response");
-                return login::Response::read_from(&mut stream)
-                    .map(|f| f(header))
-                    .and_then(FromServer::validate)
-                    .map(Response::Login)
-                    .map(Some);
+            if closed {
+                reassembly.remove(seq);
             }
-            server::TYPE => {
-                debug!("Received server message");
-                self.ack(
-                    &Message::read_from(&mut stream)
-                        .map(|f| f(header))
-                        .and_then(FromServer::validate)?,
-                );
-            }
-            other => {
-                error!("Received packet of invalid type: {other:#04X}");
+
+            !closed
+        });
+
+        if self.login.as_ref().is_some_and(oneshot::Sender::is_closed) {
+            self.login = None;
+        }
+
+        for (seq, missing) in self.reassembly.expire(MULTIPART_TIMEOUT) {
+            if let Some(waiter) = self.commands.remove(&seq) {
+                warn!("Missing parts {missing:?} of response #{seq}");
+                reply(waiter, Err(Error::TimedOut));
             }
         }
+    }
 
-        Ok(None)
+    /// Remove the waiter of a command alongside the parts of its response received so far,
+    /// so that they cannot merge into the response to a later command reusing the sequence number.
+    fn remove_command(&mut self, seq: u8) -> Option<Waiter> {
+        self.reassembly.remove(seq);
+        self.commands.remove(&seq)
     }
 
-    async fn forward(&self, response: std::io::Result<Response>) {
-        debug!("Forwarding response from UDP stream");
-        trace!("Response: {response:?}");
+    /// Fail all pending requests after the session has been lost.
+    ///
+    /// An I/O error causing the loss is shared between the waiters,
+    /// each of which receives an I/O error of the same kind wrapping it.
+    fn fail_all(&mut self, error: Error) {
+        self.reassembly.clear();
+        let cause = match error {
+            Error::Io(error) => Some(Arc::new(error)),
+            _ => None,
+        };
+        let waiters = self
+            .login
+            .take()
+            .into_iter()
+            .chain(self.commands.drain().map(|(_, waiter)| waiter));
 
-        if let Err(error) = self.responses.send(response).await {
-            error!("Error sending response: {error}");
+        for waiter in waiters {
+            let error = cause.as_ref().map_or(Error::Disconnected, |cause| {
+                Error::Io(std::io::Error::new(cause.kind(), cause.clone()))
+            });
+            reply(waiter, Err(error));
         }
     }
 
-    fn ack(&self, message: &Message) {
+    async fn ack(&self, message: &Message) {
         debug!("Ack'ing message #{}", message.seq());
         trace!("Message: {message:?}");
 
         if let Err(error) = self
             .udp_socket
-            .send(<[u8; 9]>::from(Ack::new(message.seq())).as_ref())
+            .send(&<[u8; 9]>::from(Ack::new(message.seq())))
+            .await
         {
             error!("Error sending ack: {error}");
         }
     }
 
-    fn keepalive(&self) {
+    fn publish(&self, message: &Message) {
+        debug!("Publishing message #{}", message.seq());
+        let message =
+            ServerMessage::new(message.seq(), SystemTime::now(), message.payload().to_vec());
+
+        if let Err(error) = self.messages.send(message) {
+            trace!("No subscribers for server message: {error}");
+        }
+    }
+
+    /// Send a keepalive packet if necessary.
+    ///
+    /// Returns `false` if too many keepalive packets went unacknowledged.
+    async fn keepalive(&mut self) -> bool {
         debug!("Performing keepalive check");
 
-        if self.needs_keepalive() {
-            debug!("Need to send a keepalive message");
+        if self.last_sent.elapsed() < self.settings.borrow().keepalive_interval {
+            debug!("No need to send keepalive message");
+            return true;
+        }
 
-            if let Err(error) = self.send(self.keepalive_packet()) {
-                error!("Error sending keepalive packet: {error}");
+        if let Some(seq) = self.keepalive.take() {
+            warn!("Server did not acknowledge keepalive #{seq}");
+            self.missed_keepalives += 1;
+
+            if self.missed_keepalives >= MAX_MISSED_KEEPALIVES {
+                error!("Server did not acknowledge {MAX_MISSED_KEEPALIVES} keepalives");
+                return false;
             }
-        } else {
-            debug!("No need to send keepalive message");
         }
+
+        let seq = self.seq.fetch_add(1, SeqCst);
+        debug!("Sending keepalive #{seq}");
+
+        if let Err(error) = self
+            .send(Request::Command(command::Request::keepalive(seq)))
+            .await
+        {
+            error!("Error sending keepalive packet: {error}");
+            return !is_session_loss(&Error::Io(error));
+        }
+
+        self.keepalive = Some(seq);
+        true
     }
 
-    fn needs_keepalive(&self) -> bool {
-        self.last_command
-            .and_then(|last_command| {
-                last_command
-                    .elapsed()
-                    .inspect_err(|error| warn!("{error}"))
-                    .ok()
-                    .map(|elapsed| elapsed > IDLE_TIMEOUT / 2)
-            })
-            .unwrap_or_default()
+    /// Recover from a lost session by reconnecting to the server and logging in again.
+    ///
+    /// Returns `false` if the handler should stop.
+    async fn recover(&mut self) -> bool {
+        warn!("Session lost");
+        self.fail_all(Error::Disconnected);
+        self.keepalive = None;
+        self.missed_keepalives = 0;
+        self.publish_event(SessionEvent::Lost);
+
+        let Some(credentials) = self.credentials.clone() else {
+            debug!("Not logged in. Not reconnecting.");
+            self.last_sent = Instant::now();
+            return true;
+        };
+
+        let address = match self.udp_socket.peer_addr() {
+            Ok(address) => address,
+            Err(error) => {
+                error!("Cannot determine server address: {error}");
+                self.publish_event(SessionEvent::GaveUp);
+                return false;
+            }
+        };
+
+        for attempt in 1.. {
+            let Some(delay) = self.settings.borrow().retry_policy.delay(attempt) else {
+                break;
+            };
+
+            debug!("Reconnecting to {address} in {delay:?}");
+            sleep(delay).await;
+
+            match self.reconnect(address, credentials.clone()).await {
+                Ok(()) => {
+                    debug!("Reconnected after {attempt} attempts");
+                    self.publish_event(SessionEvent::Reconnected { attempts: attempt });
+                    return true;
+                }
+                Err(error) => warn!("Reconnect attempt #{attempt} failed: {error}"),
+            }
+        }
+
+        error!("Giving up reconnecting to {address}");
+        self.publish_event(SessionEvent::GaveUp);
+        false
     }
 
-    fn keepalive_packet(&self) -> Request {
-        Request::Command(command::Request::keepalive(self.seq.fetch_add(1, SeqCst)))
+    async fn reconnect(
+        &mut self,
+        address: SocketAddr,
+        credentials: login::Request,
+    ) -> Result<(), Error> {
+        let Settings {
+            connect_timeout,
+            bind_address,
+            ..
+        } = *self.settings.borrow();
+        let udp_socket = UdpSocket::bind(SocketAddr::new(
+            bind_address.unwrap_or_else(|| unspecified(address)),
+            0,
+        ))
+        .await?;
+        udp_socket.connect(address).await?;
+        let bytes: Box<[u8]> = credentials.into();
+        udp_socket.send(&bytes).await?;
+
+        let response = timeout(connect_timeout, async {
+            loop {
+                let size = udp_socket.recv(&mut self.buffer).await?;
+
+                // Server messages will be re-sent by the server until they are acknowledged.
+                if let Response::Login(response) =
+                    Response::decode(&self.buffer[..size.min(self.buffer.len())])?
+                {
+                    return Ok::<_, Error>(response);
+                }
+            }
+        })
+        .await
+        .map_err(|_| Error::TimedOut)??;
+
+        if !response.success() {
+            return Err(Error::AuthenticationFailed);
+        }
+
+        self.udp_socket = udp_socket;
+        self.seen = SeqWindow::new();
+        self.last_sent = Instant::now();
+        Ok(())
+    }
+
+    fn publish_event(&self, event: SessionEvent) {
+        if let Err(error) = self.events.send(event) {
+            trace!("No subscribers for session event: {error}");
+        }
+    }
+}
+
+/// Returns the unspecified IP address of the given address' family.
+pub const fn unspecified(address: SocketAddr) -> IpAddr {
+    if address.is_ipv4() {
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    } else {
+        IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+    }
+}
+
+fn is_session_loss(error: &Error) -> bool {
+    let Error::Io(error) = error else {
+        return matches!(error, Error::Disconnected);
+    };
+
+    matches!(
+        error.kind(),
+        ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::HostUnreachable
+            | ErrorKind::NetworkUnreachable
+    )
+}
+
+fn reply(waiter: Waiter, result: Result<CommunicationResult, Error>) {
+    if waiter.send(result).is_err() {
+        debug!("Requester went away before receiving the result");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use tokio::net::UdpSocket;
+
+    use crate::battleye::Client;
+    use crate::battleye::packet::{Request, Response, command, login};
+    use crate::{Connect, Error, RCon};
+
+    #[tokio::test]
+    async fn test_discard_bad_checksum() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = server.local_addr().unwrap();
+
+        let fake_server = tokio::spawn(async move {
+            let mut buffer = [0; 512];
+            let (size, client) = server.recv_from(&mut buffer).await.unwrap();
+            assert!(matches!(
+                Request::decode(&buffer[..size]).unwrap(),
+                Request::Login(_)
+            ));
+            let login: Box<[u8]> = Response::Login(login::Response::new(true)).into();
+            server.send_to(&login, client).await.unwrap();
+
+            let (size, _) = server.recv_from(&mut buffer).await.unwrap();
+            let Request::Command(request) = Request::decode(&buffer[..size]).unwrap() else {
+                panic!("Expected command request");
+            };
+            let response: Box<[u8]> =
+                Response::Command(command::Response::new(request.seq(), b"pong".to_vec())).into();
+            let mut corrupt = response.to_vec();
+            corrupt[2] ^= 0xFF;
+            server.send_to(&corrupt, client).await.unwrap();
+            server.send_to(&response, client).await.unwrap();
+        });
+
+        let mut client = Client::builder()
+            .connect(address)
+            .await
+            .unwrap()
+            .login("secret")
+            .await
+            .unwrap();
+        assert_eq!(client.run_utf8("ping").await.unwrap(), "pong");
+        fake_server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fail_pending_requests_with_cause() {
+        let closed = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = closed.local_addr().unwrap();
+        drop(closed);
+
+        let udp_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        udp_socket.connect(address).await.unwrap();
+        let result = Client::builder().build(udp_socket).login("secret").await;
+
+        assert!(
+            matches!(&result, Err(Error::Io(error)) if error.kind() == ErrorKind::ConnectionRefused),
+            "Unexpected result: {result:?}"
+        );
     }
 }
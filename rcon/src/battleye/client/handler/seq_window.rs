@@ -0,0 +1,98 @@
+/// Size of the window of sequence numbers considered as recently seen.
+///
+/// Sequence numbers are `u8`s that wrap around, so half of the value range
+/// is treated as being behind and the other half as being ahead of the latest one.
+const WINDOW_SIZE: u8 = 128;
+
+/// A sliding window of recently seen sequence numbers of server messages.
+#[derive(Debug)]
+pub struct SeqWindow {
+    latest: Option<u8>,
+    seen: [bool; 256],
+}
+
+impl SeqWindow {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            latest: None,
+            seen: [false; 256],
+        }
+    }
+
+    /// Mark the sequence number as seen.
+    ///
+    /// Returns `true` if the sequence number has not been seen before.
+    pub fn insert(&mut self, seq: u8) -> bool {
+        let Some(latest) = self.latest else {
+            self.latest = Some(seq);
+            self.seen[usize::from(seq)] = true;
+            return true;
+        };
+
+        let distance = seq.wrapping_sub(latest);
+
+        if distance == 0 {
+            return false;
+        }
+
+        if distance <= WINDOW_SIZE {
+            // Slots entering the window may hold stale entries from before the last wrap-around.
+            for offset in 1..=distance {
+                self.seen[usize::from(latest.wrapping_add(offset))] = false;
+            }
+
+            self.latest = Some(seq);
+            self.seen[usize::from(seq)] = true;
+            return true;
+        }
+
+        !std::mem::replace(&mut self.seen[usize::from(seq)], true)
+    }
+}
+
+impl Default for SeqWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SeqWindow;
+
+    #[test]
+    fn test_duplicates() {
+        let mut window = SeqWindow::new();
+        assert!(window.insert(0));
+        assert!(!window.insert(0));
+        assert!(window.insert(1));
+        assert!(!window.insert(0));
+        assert!(!window.insert(1));
+    }
+
+    #[test]
+    fn test_out_of_order() {
+        let mut window = SeqWindow::new();
+        assert!(window.insert(2));
+        assert!(window.insert(0));
+        assert!(window.insert(1));
+        assert!(!window.insert(2));
+    }
+
+    #[test]
+    fn test_wrap_around() {
+        let mut window = SeqWindow::new();
+
+        for seq in 0..=u8::MAX {
+            assert!(window.insert(seq));
+        }
+
+        assert!(!window.insert(u8::MAX));
+
+        for seq in 0..=u8::MAX {
+            assert!(window.insert(seq));
+            assert!(!window.insert(seq));
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use log::{debug, trace};
+
+use crate::Error;
+use crate::battleye::packet::command;
+
+/// Marker byte indicating a multipart command response.
+///
+/// Multipart responses carry the header `0x00 | total | index` in front of their payload.
+const MULTIPART: u8 = 0x00;
+
+/// Reassembles command responses that have been split into multiple packets.
+#[derive(Debug, Default)]
+pub struct Reassembly {
+    pending: HashMap<u8, Parts>,
+}
+
+impl Reassembly {
+    /// Add a command response.
+    ///
+    /// Returns the complete payload of the response if all of its parts have been received.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MalformedPacket`] if the multipart header is invalid
+    /// or inconsistent with previously received parts.
+    pub fn push(&mut self, response: &command::Response) -> Result<Option<Vec<u8>>, Error> {
+        let seq = response.seq();
+
+        let [MULTIPART, total, index, data @ ..] = response.payload() else {
+            trace!("Received single-packet response #{seq}");
+            return Ok(Some(response.payload().to_vec()));
+        };
+
+        debug!("Received part {index} of {total} of response #{seq}");
+
+        if index >= total {
+            return Err(Error::malformed_packet(
+                format!("Invalid part index {index} of {total} of response #{seq}"),
+                response.payload(),
+            ));
+        }
+
+        let parts = self
+            .pending
+            .entry(seq)
+            .or_insert_with(|| Parts::new(*total));
+
+        if parts.parts.len() != usize::from(*total) {
+            let expected = parts.parts.len();
+            self.pending.remove(&seq);
+            return Err(Error::malformed_packet(
+                format!("Part count of response #{seq} changed from {expected} to {total}"),
+                response.payload(),
+            ));
+        }
+
+        parts.parts[usize::from(*index)] = Some(data.to_vec());
+        parts.updated = Instant::now();
+
+        if !parts.is_complete() {
+            return Ok(None);
+        }
+
+        debug!("Received all parts of response #{seq}");
+        Ok(self
+            .pending
+            .remove(&seq)
+            .map(|parts| parts.parts.into_iter().flatten().flatten().collect()))
+    }
+
+    /// Discard all responses that did not receive a part within the given timeout.
+    ///
+    /// Returns the sequence numbers of the discarded responses
+    /// alongside the indices of their missing parts.
+    pub fn expire(&mut self, timeout: Duration) -> Vec<(u8, Vec<u8>)> {
+        let mut expired = Vec::new();
+        self.pending.retain(|seq, parts| {
+            if parts.updated.elapsed() < timeout {
+                return true;
+            }
+
+            expired.push((*seq, parts.missing()));
+            false
+        });
+        expired
+    }
+
+    /// Discard the parts of the given response received so far.
+    pub fn remove(&mut self, seq: u8) {
+        if self.pending.remove(&seq).is_some() {
+            debug!("Discarding parts of response #{seq}");
+        }
+    }
+
+    /// Discard all pending responses.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// The parts of a multipart response received so far.
+#[derive(Debug)]
+struct Parts {
+    parts: Vec<Option<Vec<u8>>>,
+    updated: Instant,
+}
+
+impl Parts {
+    fn new(total: u8) -> Self {
+        Self {
+            parts: vec![None; usize::from(total)],
+            updated: Instant::now(),
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.parts.iter().all(Option::is_some)
+    }
+
+    fn missing(&self) -> Vec<u8> {
+        (0..=u8::MAX)
+            .zip(&self.parts)
+            .filter_map(|(index, part)| part.is_none().then_some(index))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Reassembly;
+    use crate::battleye::packet::command::Response;
+
+    fn response(seq: u8, payload: &[u8]) -> Response {
+        Response::new(seq, payload.to_vec())
+    }
+
+    #[test]
+    fn test_single_packet() {
+        let mut reassembly = Reassembly::default();
+        assert_eq!(
+            reassembly.push(&response(1, b"Hello")).ok(),
+            Some(Some(b"Hello".to_vec()))
+        );
+        assert!(reassembly.expire(Duration::ZERO).is_empty());
+    }
+
+    #[test]
+    fn test_multipart_out_of_order() {
+        let mut reassembly = Reassembly::default();
+        assert_eq!(
+            reassembly.push(&response(7, b"\x00\x03\x02!")).ok(),
+            Some(None)
+        );
+        assert_eq!(
+            reassembly.push(&response(7, b"\x00\x03\x00Hello")).ok(),
+            Some(None)
+        );
+        assert_eq!(
+            reassembly.push(&response(7, b"\x00\x03\x01 World")).ok(),
+            Some(Some(b"Hello World!".to_vec()))
+        );
+        assert!(reassembly.expire(Duration::ZERO).is_empty());
+    }
+
+    #[test]
+    fn test_missing_parts() {
+        let mut reassembly = Reassembly::default();
+        assert_eq!(
+            reassembly.push(&response(7, b"\x00\x04\x01data")).ok(),
+            Some(None)
+        );
+        assert!(reassembly.expire(Duration::from_secs(10)).is_empty());
+        assert_eq!(reassembly.expire(Duration::ZERO), vec![(7, vec![0, 2, 3])]);
+        assert!(reassembly.expire(Duration::ZERO).is_empty());
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut reassembly = Reassembly::default();
+        assert_eq!(
+            reassembly.push(&response(7, b"\x00\x02\x00stale")).ok(),
+            Some(None)
+        );
+        reassembly.remove(7);
+        assert_eq!(
+            reassembly.push(&response(7, b"\x00\x02\x01World")).ok(),
+            Some(None)
+        );
+        assert_eq!(
+            reassembly.push(&response(7, b"\x00\x02\x00Hello ")).ok(),
+            Some(Some(b"Hello World".to_vec()))
+        );
+    }
+
+    #[test]
+    fn test_invalid_index() {
+        let mut reassembly = Reassembly::default();
+        assert!(reassembly.push(&response(7, b"\x00\x02\x02data")).is_err());
+    }
+}
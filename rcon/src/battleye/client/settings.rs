@@ -0,0 +1,15 @@
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::RetryPolicy;
+
+/// Settings of the handler.
+///
+/// The keepalive interval and retry policy can be changed at runtime.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Settings {
+    pub keepalive_interval: Duration,
+    pub retry_policy: RetryPolicy,
+    pub connect_timeout: Duration,
+    pub bind_address: Option<IpAddr>,
+}
@@ -0,0 +1,84 @@
+//! [`tokio_util::codec`] implementations for `BattlEye Rcon` datagrams.
+//!
+//! The codecs are meant to be used with `tokio_util::udp::UdpFramed`,
+//! which passes each received datagram to the decoder as a whole.
+
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+
+use super::packet::{Request, Response};
+use crate::Error;
+
+/// Codec for the client side, decoding [`Response`]s and encoding [`Request`]s.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ClientCodec;
+
+impl Decoder for ClientCodec {
+    type Item = Response;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.is_empty() {
+            return Ok(None);
+        }
+
+        Response::decode(&src.split()).map(Some)
+    }
+}
+
+impl Encoder<Request> for ClientCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Request, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(&Box::<[u8]>::from(item));
+        Ok(())
+    }
+}
+
+/// Codec for the server side, decoding [`Request`]s and encoding [`Response`]s.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ServerCodec;
+
+impl Decoder for ServerCodec {
+    type Item = Request;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.is_empty() {
+            return Ok(None);
+        }
+
+        Request::decode(&src.split()).map(Some)
+    }
+}
+
+impl Encoder<Response> for ServerCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Response, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        dst.extend_from_slice(&Box::<[u8]>::from(item));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    use super::{ClientCodec, ServerCodec};
+    use crate::battleye::packet::{Request, Response, command};
+
+    #[test]
+    fn test_codecs() {
+        let request = Request::Command(command::Request::command(3, b"players"));
+        let mut buffer = BytesMut::new();
+        ClientCodec.encode(request.clone(), &mut buffer).unwrap();
+        assert_eq!(ServerCodec.decode(&mut buffer).unwrap(), Some(request));
+        assert_eq!(ServerCodec.decode(&mut buffer).unwrap(), None);
+
+        let response = Response::Command(command::Response::new(3, b"Players:".to_vec()));
+        ServerCodec.encode(response.clone(), &mut buffer).unwrap();
+        assert_eq!(ClientCodec.decode(&mut buffer).unwrap(), Some(response));
+    }
+}
@@ -0,0 +1,158 @@
+use std::net::SocketAddr;
+
+use tokio::net::{ToSocketAddrs, UdpSocket};
+
+use super::{Client, ClientBuilder};
+use crate::resolve::connect_any;
+use crate::{Connect, Error};
+
+/// An unauthenticated connection to a `BattlEye Rcon` server.
+///
+/// Log in to obtain a [`Client`] that can run commands.
+#[derive(Debug)]
+pub struct Connection {
+    target: Target,
+}
+
+impl Connection {
+    /// Creates a new connection on a connected UDP socket.
+    ///
+    /// Use [`ClientBuilder`] to customize further settings.
+    #[must_use]
+    pub fn new(udp_socket: UdpSocket, channel_size: usize, buf_size: usize) -> Self {
+        ClientBuilder::new()
+            .channel_size(channel_size)
+            .buf_size(buf_size)
+            .build(udp_socket)
+    }
+
+    pub(super) const fn from_client(client: Client) -> Self {
+        Self {
+            target: Target::Client(client),
+        }
+    }
+
+    pub(super) const fn from_addresses(builder: ClientBuilder, addresses: Vec<SocketAddr>) -> Self {
+        Self {
+            target: Target::Addresses { builder, addresses },
+        }
+    }
+}
+
+impl Connect for Connection {
+    type Client = Client;
+
+    async fn connect<T>(address: T) -> Result<Self, Error>
+    where
+        T: ToSocketAddrs + Send,
+    {
+        ClientBuilder::new().connect(address).await
+    }
+
+    /// Perform a login, consuming the connection.
+    ///
+    /// Since UDP sockets connect without contacting the server,
+    /// each resolved address is probed with the login in turn,
+    /// until a server responds within the builder's connect timeout.
+    /// Use [`Client::peer_addr`] to find out which address responded.
+    async fn login<T>(self, password: T) -> Result<Self::Client, Error>
+    where
+        T: AsRef<[u8]> + Send,
+    {
+        let password = password.as_ref();
+
+        match self.target {
+            Target::Client(client) => login(client, password).await,
+            Target::Addresses { builder, addresses } => {
+                connect_any(addresses.as_slice(), |address| {
+                    builder.probe(address, password)
+                })
+                .await?
+                .ok_or(Error::AuthenticationFailed)
+            }
+        }
+    }
+}
+
+/// The server to log in to.
+#[derive(Debug)]
+enum Target {
+    /// A client on an already connected UDP socket.
+    Client(Client),
+    /// The resolved addresses of the server, each of which is probed with the login in turn.
+    Addresses {
+        builder: ClientBuilder,
+        addresses: Vec<SocketAddr>,
+    },
+}
+
+async fn login(client: Client, password: &[u8]) -> Result<Client, Error> {
+    if client.login(password).await? {
+        Ok(client)
+    } else {
+        Err(Error::AuthenticationFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::time::Duration;
+
+    use tokio::net::UdpSocket;
+
+    use crate::battleye::Client;
+    use crate::battleye::packet::{Request, Response, login};
+    use crate::{Connect, Error};
+
+    /// Spawn a fake server answering a single login request.
+    async fn spawn_server(success: bool) -> SocketAddr {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = server.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut buffer = [0; 512];
+            let (size, client) = server.recv_from(&mut buffer).await.unwrap();
+            assert!(matches!(
+                Request::decode(&buffer[..size]).unwrap(),
+                Request::Login(_)
+            ));
+            let login: Box<[u8]> = Response::Login(login::Response::new(success)).into();
+            server.send_to(&login, client).await.unwrap();
+        });
+
+        address
+    }
+
+    #[tokio::test]
+    async fn test_login_probes_addresses() {
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let server = spawn_server(true).await;
+        let client = Client::builder()
+            .connect_timeout(Duration::from_millis(100))
+            .connect([silent.local_addr().unwrap(), server].as_slice())
+            .await
+            .unwrap()
+            .login("secret")
+            .await
+            .unwrap();
+
+        assert_eq!(client.peer_addr().unwrap(), server);
+    }
+
+    #[tokio::test]
+    async fn test_login_rejected() {
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let server = spawn_server(false).await;
+        let connection = Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .connect([server, silent.local_addr().unwrap()].as_slice())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            connection.login("wrong").await,
+            Err(Error::AuthenticationFailed)
+        ));
+    }
+}
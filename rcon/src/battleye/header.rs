@@ -1,12 +1,17 @@
-use std::io::{Error, ErrorKind};
+//! The header common to all `BattlEye Rcon` packets.
 
 use crc::{CRC_32_ISO_HDLC, Crc};
 use log::{debug, error};
 
+use crate::Error;
+
 const INFIX: u8 = 0xFF;
 const PREFIX: &[u8; 2] = b"BE";
 const CRC32: Crc<u32> = Crc::<u32>::new(&CRC_32_ISO_HDLC);
 
+/// The header of a `BattlEye Rcon` packet.
+///
+/// The header contains a checksum over the packet's type and payload.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Header {
     prefix: [u8; 2],
@@ -16,8 +21,10 @@ pub struct Header {
 }
 
 impl Header {
+    /// The size of the header in bytes.
     pub const SIZE: usize = 8;
 
+    /// Create a new header from its raw fields.
     #[must_use]
     pub const fn new(prefix: [u8; 2], crc32: u32, infix: u8, typ: u8) -> Self {
         Self {
@@ -28,37 +35,60 @@ impl Header {
         }
     }
 
+    /// Create a header for a packet of the given type and payload.
     #[must_use]
     pub const fn create(typ: u8, payload: &[u8]) -> Self {
-        Self::new(*PREFIX, crc32(typ, INFIX, payload), INFIX, typ)
+        Self::new(*PREFIX, crc32(typ, INFIX, &[payload]), INFIX, typ)
     }
 
-    pub fn read_from<T>(src: T) -> std::io::Result<Self>
-    where
-        T: Iterator<Item = u8>,
-    {
-        let buffer: [u8; Self::SIZE] = src
-            .take(Self::SIZE)
-            .collect::<Vec<_>>()
-            .try_into()
-            .map_err(|_| {
-                Error::new(
-                    ErrorKind::UnexpectedEof,
-                    "Too few bytes to construct header",
-                )
-            })?;
-        Ok(Self::from(buffer))
+    /// Create a header for a packet of the given type whose payload is a sequence number followed by `data`.
+    #[must_use]
+    pub const fn create_sequenced(typ: u8, seq: u8, data: &[u8]) -> Self {
+        Self::new(*PREFIX, crc32(typ, INFIX, &[&[seq], data]), INFIX, typ)
     }
 
+    /// Parse the header at the start of a datagram and validate the checksum of the remaining payload.
+    ///
+    /// Returns the header and the payload following it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MalformedPacket`] if the datagram is too short or does not start with `BE`.
+    /// Returns [`Error::ChecksumMismatch`] if the checksum does not match the payload.
+    pub fn decode(datagram: &[u8]) -> Result<(Self, &[u8]), Error> {
+        let Some((header, payload)) = datagram.split_first_chunk::<{ Self::SIZE }>() else {
+            return Err(Error::malformed_packet(
+                "Too few bytes to construct header",
+                datagram,
+            ));
+        };
+        let header = Self::from(*header);
+
+        if &header.prefix != PREFIX {
+            return Err(Error::malformed_packet("Invalid prefix", datagram));
+        }
+
+        if !header.is_valid(payload) {
+            return Err(Error::ChecksumMismatch);
+        }
+
+        Ok((header, payload))
+    }
+
+    /// Returns the packet type.
     #[must_use]
     pub const fn typ(self) -> u8 {
         self.typ
     }
 
+    /// Calculate the checksum of the given payload for this header's packet type.
+    #[must_use]
     pub const fn crc32(self, payload: &[u8]) -> u32 {
-        crc32(self.typ, self.infix, payload)
+        crc32(self.typ, self.infix, &[payload])
     }
 
+    /// Check whether the header's checksum matches the given payload.
+    #[must_use]
     pub fn is_valid(self, payload: &[u8]) -> bool {
         let crc = self.crc32(payload);
 
@@ -91,20 +121,70 @@ impl From<Header> for [u8; Header::SIZE] {
     }
 }
 
-const fn crc32(typ: u8, infix: u8, payload: &[u8]) -> u32 {
+/// Check that a header belongs to a packet of the expected type.
+pub(crate) fn expect_type(header: Header, typ: u8, datagram: &[u8]) -> Result<(), Error> {
+    if header.typ == typ {
+        Ok(())
+    } else {
+        Err(Error::malformed_packet(
+            format!(
+                "Expected packet type {typ:#04X}, but got {:#04X}",
+                header.typ
+            ),
+            datagram,
+        ))
+    }
+}
+
+/// Calculate the checksum of a packet whose payload consists of the given parts.
+const fn crc32(typ: u8, infix: u8, parts: &[&[u8]]) -> u32 {
     let mut crc = CRC32.digest();
     crc.update(&[infix, typ]);
-    crc.update(payload);
+    let mut index = 0;
+
+    while index < parts.len() {
+        crc.update(parts[index]);
+        index += 1;
+    }
+
     crc.finalize()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::crc32;
+    use super::{Header, crc32};
+    use crate::Error;
 
     #[test]
     fn test_crc32() {
-        let checksum = crc32(0x00, 0xff, b"password");
+        let checksum = crc32(0x00, 0xff, &[b"password"]);
         assert_eq!(checksum, 0x522d_26de);
+        assert_eq!(crc32(0x00, 0xff, &[b"pass", b"", b"word"]), checksum);
+    }
+
+    #[test]
+    fn test_create_sequenced() {
+        assert_eq!(
+            Header::create_sequenced(0x01, 7, b"players"),
+            Header::create(0x01, b"\x07players")
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        let header: [u8; Header::SIZE] = Header::create(0x00, b"password").into();
+        let datagram = [header.as_slice(), b"password"].concat();
+        let (decoded, payload) = Header::decode(&datagram).unwrap();
+        assert_eq!(decoded.typ(), 0x00);
+        assert_eq!(payload, b"password");
+
+        assert!(matches!(
+            Header::decode(&[header.as_slice(), b"passwort"].concat()),
+            Err(Error::ChecksumMismatch)
+        ));
+        assert!(matches!(
+            Header::decode(&header[..4]),
+            Err(Error::MalformedPacket { .. })
+        ));
     }
 }
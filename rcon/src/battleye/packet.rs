@@ -1,8 +1,21 @@
+//! Sans-IO encoding and decoding of `BattlEye Rcon` packets.
+//!
+//! Each packet is sent as a single UDP datagram.
+//! Decode datagrams with [`Request::decode`] or [`Response::decode`],
+//! depending on whether they were sent by the client or the server,
+//! and encode packets by converting them into `Box<[u8]>`.
+
+pub use sequenced::Sequenced;
+
+use crate::Error;
+use crate::battleye::header::Header;
+
 pub mod command;
 pub mod login;
+mod sequenced;
 pub mod server;
 
-/// Request packet types.
+/// Packets sent by the client.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Request {
     /// A command request.
@@ -10,24 +23,150 @@ pub enum Request {
 
     /// A login request.
     Login(login::Request),
+
+    /// An acknowledgement of a server message.
+    Ack(server::Ack),
 }
 
-/// Response packet types.
+impl Request {
+    /// Parse a packet sent by the client from a datagram.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MalformedPacket`] if the datagram is not a valid client packet.
+    /// Returns [`Error::ChecksumMismatch`] if the checksum does not match the payload.
+    pub fn decode(datagram: &[u8]) -> Result<Self, Error> {
+        let (header, payload) = Header::decode(datagram)?;
+
+        match header.typ() {
+            command::TYPE => {
+                command::Request::from_parts(header, payload, datagram).map(Self::Command)
+            }
+            login::TYPE => Ok(Self::Login(login::Request::from_parts(header, payload))),
+            server::TYPE => server::Ack::from_parts(header, payload, datagram).map(Self::Ack),
+            other => Err(Error::malformed_packet(
+                format!("Invalid packet type: {other:#04X}"),
+                datagram,
+            )),
+        }
+    }
+}
+
+impl From<Request> for Box<[u8]> {
+    fn from(request: Request) -> Self {
+        match request {
+            Request::Command(request) => request.into(),
+            Request::Login(request) => request.into(),
+            Request::Ack(ack) => Box::new(<[u8; 9]>::from(ack)),
+        }
+    }
+}
+
+/// Packets sent by the server.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum Response {
-    /// A Command response.
+    /// A command response.
     Command(command::Response),
 
     /// A login response.
     Login(login::Response),
+
+    /// A server message.
+    Message(server::Message),
+}
+
+impl Response {
+    /// Parse a packet sent by the server from a datagram.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MalformedPacket`] if the datagram is not a valid server packet.
+    /// Returns [`Error::ChecksumMismatch`] if the checksum does not match the payload.
+    pub fn decode(datagram: &[u8]) -> Result<Self, Error> {
+        let (header, payload) = Header::decode(datagram)?;
+
+        match header.typ() {
+            command::TYPE => {
+                command::Response::from_parts(header, payload, datagram).map(Self::Command)
+            }
+            login::TYPE => login::Response::from_parts(header, payload, datagram).map(Self::Login),
+            server::TYPE => {
+                server::Message::from_parts(header, payload, datagram).map(Self::Message)
+            }
+            other => Err(Error::malformed_packet(
+                format!("Invalid packet type: {other:#04X}"),
+                datagram,
+            )),
+        }
+    }
+}
+
+impl From<Response> for Box<[u8]> {
+    fn from(response: Response) -> Self {
+        match response {
+            Response::Command(response) => response.into(),
+            Response::Login(response) => response.into(),
+            Response::Message(message) => message.into(),
+        }
+    }
 }
 
 /// A result of a client-server communication.
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub enum CommunicationResult {
+pub(crate) enum CommunicationResult {
     /// Result of a command.
     Command(Vec<u8>),
 
     /// Result of a login.
     Login(login::Response),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Request, Response, command, login, server};
+    use crate::Error;
+
+    #[test]
+    fn test_request_round_trip() {
+        for request in [
+            Request::Command(command::Request::command(7, b"players")),
+            Request::Command(command::Request::keepalive(8)),
+            Request::Login(login::Request::from(b"password".as_slice())),
+            Request::Ack(server::Ack::new(9)),
+        ] {
+            let bytes: Box<[u8]> = request.clone().into();
+            assert_eq!(Request::decode(&bytes).unwrap(), request);
+        }
+    }
+
+    #[test]
+    fn test_response_round_trip() {
+        for response in [
+            Response::Command(command::Response::new(7, b"Players:".to_vec())),
+            Response::Login(login::Response::new(true)),
+            Response::Message(server::Message::new(9, b"Hello".to_vec())),
+        ] {
+            let bytes: Box<[u8]> = response.clone().into();
+            assert_eq!(Response::decode(&bytes).unwrap(), response);
+        }
+    }
+
+    #[test]
+    fn test_decode_login_response() {
+        let datagram = b"BE\x69\xdd\xde\x36\xff\x00\x01";
+        assert_eq!(
+            Response::decode(datagram).unwrap(),
+            Response::Login(login::Response::new(true))
+        );
+    }
+
+    #[test]
+    fn test_sequenced_packets_check_type() {
+        let bytes: Box<[u8]> = command::Response::new(7, b"Players:".to_vec()).into();
+        assert!(matches!(
+            server::Message::decode(&bytes),
+            Err(Error::MalformedPacket { .. })
+        ));
+        assert!(command::Response::decode(&bytes).is_ok());
+    }
+}
@@ -1,7 +1,10 @@
+//! Command packets.
+
 pub use request::Request;
 pub use response::Response;
 
 mod request;
 mod response;
 
+/// Packet type of commands and their responses.
 pub const TYPE: u8 = 0x01;
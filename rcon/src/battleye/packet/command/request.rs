@@ -1,6 +1,10 @@
 use super::TYPE;
-use crate::battleye::header::Header;
+use crate::Error;
+use crate::battleye::header::{Header, expect_type};
 
+/// A command sent by the client.
+///
+/// Commands with an empty payload serve as keepalive packets.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Request {
     header: Header,
@@ -9,8 +13,7 @@ pub struct Request {
 }
 
 impl Request {
-    #[must_use]
-    pub const fn new(header: Header, seq: u8, command: Vec<u8>) -> Self {
+    const fn new(header: Header, seq: u8, command: Vec<u8>) -> Self {
         Self {
             header,
             seq,
@@ -18,26 +21,59 @@ impl Request {
         }
     }
 
+    /// Create a command request with the given sequence number.
     #[must_use]
     pub fn command(seq: u8, command: &[u8]) -> Self {
         Self::new(
-            Header::create(
-                TYPE,
-                seq.to_le_bytes()
-                    .into_iter()
-                    .chain(command.iter().copied())
-                    .collect::<Vec<_>>()
-                    .as_slice(),
-            ),
+            Header::create_sequenced(TYPE, seq, command),
             seq,
             command.to_vec(),
         )
     }
 
+    /// Create a keepalive request with the given sequence number.
     #[must_use]
     pub const fn keepalive(seq: u8) -> Self {
         Self::new(Header::create(TYPE, &[seq]), seq, Vec::new())
     }
+
+    /// Parse a command request from a datagram.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MalformedPacket`] if the datagram is not a command request.
+    /// Returns [`Error::ChecksumMismatch`] if the checksum does not match the payload.
+    pub fn decode(datagram: &[u8]) -> Result<Self, Error> {
+        let (header, payload) = Header::decode(datagram)?;
+        expect_type(header, TYPE, datagram)?;
+        Self::from_parts(header, payload, datagram)
+    }
+
+    pub(crate) fn from_parts(
+        header: Header,
+        payload: &[u8],
+        datagram: &[u8],
+    ) -> Result<Self, Error> {
+        let [seq, command @ ..] = payload else {
+            return Err(Error::malformed_packet(
+                "Too few bytes to construct command request",
+                datagram,
+            ));
+        };
+        Ok(Self::new(header, *seq, command.to_vec()))
+    }
+
+    /// Returns the sequence number.
+    #[must_use]
+    pub const fn seq(&self) -> u8 {
+        self.seq
+    }
+
+    /// Returns the command.
+    #[must_use]
+    pub fn payload(&self) -> &[u8] {
+        &self.command
+    }
 }
 
 impl From<Request> for Box<[u8]> {
@@ -1,7 +1,10 @@
+//! Login packets.
+
 pub use request::Request;
 pub use response::Response;
 
 mod request;
 mod response;
 
+/// Packet type of logins and their responses.
 pub const TYPE: u8 = 0x00;
@@ -1,6 +1,8 @@
 use super::TYPE;
-use crate::battleye::header::Header;
+use crate::Error;
+use crate::battleye::header::{Header, expect_type};
 
+/// A login request sent by the client.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Request {
     header: Header,
@@ -8,10 +10,31 @@ pub struct Request {
 }
 
 impl Request {
-    #[must_use]
-    pub const fn new(header: Header, password: Vec<u8>) -> Self {
+    const fn new(header: Header, password: Vec<u8>) -> Self {
         Self { header, password }
     }
+
+    /// Parse a login request from a datagram.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MalformedPacket`] if the datagram is not a login request.
+    /// Returns [`Error::ChecksumMismatch`] if the checksum does not match the payload.
+    pub fn decode(datagram: &[u8]) -> Result<Self, Error> {
+        let (header, payload) = Header::decode(datagram)?;
+        expect_type(header, TYPE, datagram)?;
+        Ok(Self::from_parts(header, payload))
+    }
+
+    pub(crate) fn from_parts(header: Header, payload: &[u8]) -> Self {
+        Self::new(header, payload.to_vec())
+    }
+
+    /// Returns the password.
+    #[must_use]
+    pub fn password(&self) -> &[u8] {
+        &self.password
+    }
 }
 
 impl From<&[u8]> for Request {
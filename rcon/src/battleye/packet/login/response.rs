@@ -1,8 +1,8 @@
-use std::io::{Error, ErrorKind};
-
-use crate::battleye::from_server::FromServer;
-use crate::battleye::header::Header;
+use super::TYPE;
+use crate::Error;
+use crate::battleye::header::{Header, expect_type};
 
+/// A response to a login request sent by the server.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Response {
     header: Header,
@@ -10,32 +10,57 @@ pub struct Response {
 }
 
 impl Response {
+    /// Create a login response.
     #[must_use]
-    pub const fn new(header: Header, success: bool) -> Self {
-        Self { header, success }
+    pub const fn new(success: bool) -> Self {
+        Self {
+            header: Header::create(TYPE, &[success as u8]),
+            success,
+        }
+    }
+
+    /// Parse a login response from a datagram.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MalformedPacket`] if the datagram is not a login response.
+    /// Returns [`Error::ChecksumMismatch`] if the checksum does not match the payload.
+    pub fn decode(datagram: &[u8]) -> Result<Self, Error> {
+        let (header, payload) = Header::decode(datagram)?;
+        expect_type(header, TYPE, datagram)?;
+        Self::from_parts(header, payload, datagram)
     }
 
-    pub fn read_from<T>(mut src: T) -> std::io::Result<impl FnOnce(Header) -> Self>
-    where
-        T: Iterator<Item = u8>,
-    {
-        let success = src.next().ok_or_else(|| {
-            Error::new(
-                ErrorKind::UnexpectedEof,
-                "Too few bytes to construct login response",
-            )
-        })?;
-        Ok(move |header| Self::new(header, success != 0))
+    pub(crate) fn from_parts(
+        header: Header,
+        payload: &[u8],
+        datagram: &[u8],
+    ) -> Result<Self, Error> {
+        let [success] = payload else {
+            return Err(Error::malformed_packet(
+                "Invalid size of login response",
+                datagram,
+            ));
+        };
+        Ok(Self {
+            header,
+            success: *success != 0,
+        })
     }
 
+    /// Returns `true` if the server accepted the password.
     #[must_use]
     pub const fn success(&self) -> bool {
         self.success
     }
 }
 
-impl FromServer for Response {
-    fn is_valid(&self) -> bool {
-        self.header.is_valid(&u8::from(self.success).to_le_bytes())
+impl From<Response> for Box<[u8]> {
+    fn from(response: Response) -> Self {
+        let header: [u8; Header::SIZE] = response.header.into();
+        let mut buffer = Vec::with_capacity(Header::SIZE + 1);
+        buffer.extend_from_slice(&header);
+        buffer.push(u8::from(response.success));
+        buffer.into_boxed_slice()
     }
 }
@@ -0,0 +1,78 @@
+use crate::Error;
+use crate::battleye::header::{Header, expect_type};
+
+/// A packet of type `TYPE` sent by the server, carrying a sequence number and a payload.
+///
+/// Command responses and server messages share this structure
+/// and only differ in their packet type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Sequenced<const TYPE: u8> {
+    header: Header,
+    seq: u8,
+    payload: Vec<u8>,
+}
+
+impl<const TYPE: u8> Sequenced<TYPE> {
+    /// Create a packet with the given sequence number.
+    #[must_use]
+    pub const fn new(seq: u8, payload: Vec<u8>) -> Self {
+        Self {
+            header: Header::create_sequenced(TYPE, seq, payload.as_slice()),
+            seq,
+            payload,
+        }
+    }
+
+    /// Parse a packet from a datagram.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MalformedPacket`] if the datagram is not a packet of type `TYPE`.
+    /// Returns [`Error::ChecksumMismatch`] if the checksum does not match the payload.
+    pub fn decode(datagram: &[u8]) -> Result<Self, Error> {
+        let (header, payload) = Header::decode(datagram)?;
+        expect_type(header, TYPE, datagram)?;
+        Self::from_parts(header, payload, datagram)
+    }
+
+    pub(crate) fn from_parts(
+        header: Header,
+        payload: &[u8],
+        datagram: &[u8],
+    ) -> Result<Self, Error> {
+        let [seq, payload @ ..] = payload else {
+            return Err(Error::malformed_packet(
+                format!("Too few bytes to construct packet of type {TYPE:#04X}"),
+                datagram,
+            ));
+        };
+        Ok(Self {
+            header,
+            seq: *seq,
+            payload: payload.to_vec(),
+        })
+    }
+
+    /// Returns the sequence number.
+    #[must_use]
+    pub const fn seq(&self) -> u8 {
+        self.seq
+    }
+
+    /// Returns the payload.
+    #[must_use]
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+}
+
+impl<const TYPE: u8> From<Sequenced<TYPE>> for Box<[u8]> {
+    fn from(packet: Sequenced<TYPE>) -> Self {
+        let header: [u8; Header::SIZE] = packet.header.into();
+        let mut buffer = Vec::with_capacity(Header::SIZE + 1 + packet.payload.len());
+        buffer.extend_from_slice(&header);
+        buffer.push(packet.seq);
+        buffer.extend_from_slice(&packet.payload);
+        buffer.into_boxed_slice()
+    }
+}
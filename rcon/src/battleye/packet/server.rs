@@ -1,7 +1,10 @@
+//! Server message packets.
+
 pub use ack::Ack;
 pub use message::Message;
 
 mod ack;
 mod message;
 
+/// Packet type of server messages and their acknowledgements.
 pub const TYPE: u8 = 0x02;
@@ -1,6 +1,8 @@
 use super::TYPE;
-use crate::battleye::header::Header;
+use crate::Error;
+use crate::battleye::header::{Header, expect_type};
 
+/// An acknowledgement of a server message sent by the client.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Ack {
     header: Header,
@@ -8,6 +10,7 @@ pub struct Ack {
 }
 
 impl Ack {
+    /// Create an acknowledgement of the message with the given sequence number.
     #[must_use]
     pub const fn new(seq: u8) -> Self {
         Self {
@@ -15,6 +18,38 @@ impl Ack {
             seq,
         }
     }
+
+    /// Parse an acknowledgement from a datagram.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MalformedPacket`] if the datagram is not an acknowledgement.
+    /// Returns [`Error::ChecksumMismatch`] if the checksum does not match the payload.
+    pub fn decode(datagram: &[u8]) -> Result<Self, Error> {
+        let (header, payload) = Header::decode(datagram)?;
+        expect_type(header, TYPE, datagram)?;
+        Self::from_parts(header, payload, datagram)
+    }
+
+    pub(crate) fn from_parts(
+        header: Header,
+        payload: &[u8],
+        datagram: &[u8],
+    ) -> Result<Self, Error> {
+        let [seq] = payload else {
+            return Err(Error::malformed_packet(
+                "Invalid size of acknowledgement",
+                datagram,
+            ));
+        };
+        Ok(Self { header, seq: *seq })
+    }
+
+    /// Returns the sequence number of the acknowledged message.
+    #[must_use]
+    pub const fn seq(&self) -> u8 {
+        self.seq
+    }
 }
 
 impl From<Ack> for [u8; 9] {
@@ -1,4 +1,9 @@
-use crate::battleye::packet::command::Response;
+use super::TYPE;
+use crate::battleye::packet::Sequenced;
 
-/// Server messages have the very same structure as command responses.
-pub type Message = Response;
+/// A message pushed by the server.
+///
+/// Server messages have the very same structure as [command responses](crate::battleye::packet::command::Response).
+/// The client must acknowledge each message with an [`Ack`](super::Ack).
+/// Otherwise, the server repeats the message.
+pub type Message = Sequenced<TYPE>;
@@ -0,0 +1,583 @@
+//! Server implementation for the `BattlEye Rcon` protocol.
+//!
+//! The server listens on a UDP socket, authenticates clients with a password
+//! and dispatches their commands to a [`Handler`].
+//! Server messages can be pushed to all logged-in clients via a [`ServerHandle`].
+//! It is intended to stand in for game servers in tests.
+
+use std::collections::{HashMap, HashSet};
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use log::{debug, info, trace, warn};
+use tokio::net::{ToSocketAddrs, UdpSocket};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+use tokio::time::interval;
+use tokio::{select, spawn};
+
+use super::packet::{Request, command, login, server};
+use crate::Error;
+pub use crate::server::Handler;
+
+/// Maximum size of a UDP datagram's payload.
+const BUF_SIZE: usize = 65_507;
+
+/// Maximum payload size of a command response packet.
+///
+/// Larger responses are split into multiple packets, so that each datagram fits into a common MTU.
+const SPLIT_SIZE: usize = 1400;
+
+/// Maximum size of a command response.
+///
+/// Responses are split into at most 255 packets. Longer responses are truncated to this size.
+pub const MAX_RESPONSE_SIZE: usize = u8::MAX as usize * SPLIT_SIZE;
+
+/// Marker byte indicating a multipart command response.
+const MULTIPART: u8 = 0x00;
+
+/// Time after which a client that did not send any packets is logged out.
+const SESSION_TIMEOUT: Duration = Duration::from_secs(45);
+
+const DEFAULT_RETRANSMIT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Amount of times a server message is sent before giving up on its acknowledgement.
+const MAX_ATTEMPTS: u8 = 5;
+
+/// A `BattlEye Rcon` server.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rcon::battleye::server::Server;
+///
+/// # async fn serve() -> Result<(), rcon::Error> {
+/// let server = Server::bind(
+///     "127.0.0.1:2302",
+///     "secret",
+///     |command: Vec<u8>| async move { command },
+/// )
+/// .await?;
+/// let handle = server.handle();
+/// tokio::spawn(server.serve());
+/// handle.broadcast("Hello, world!")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Server<H> {
+    udp_socket: UdpSocket,
+    password: Vec<u8>,
+    handler: Arc<H>,
+    retransmit_interval: Duration,
+    sessions: HashMap<SocketAddr, Session>,
+    sender: UnboundedSender<Vec<u8>>,
+    receiver: UnboundedReceiver<Vec<u8>>,
+    replies: UnboundedSender<Reply>,
+    replies_receiver: UnboundedReceiver<Reply>,
+}
+
+impl<H> Server<H>
+where
+    H: Handler,
+{
+    /// Bind a new server accepting the given password to the given address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the socket cannot be bound.
+    pub async fn bind<A, P>(address: A, password: P, handler: H) -> Result<Self, Error>
+    where
+        A: ToSocketAddrs,
+        P: AsRef<[u8]>,
+    {
+        let (sender, receiver) = unbounded_channel();
+        let (replies, replies_receiver) = unbounded_channel();
+        Ok(Self {
+            udp_socket: UdpSocket::bind(address).await?,
+            password: password.as_ref().to_vec(),
+            handler: Arc::new(handler),
+            retransmit_interval: DEFAULT_RETRANSMIT_INTERVAL,
+            sessions: HashMap::new(),
+            sender,
+            receiver,
+            replies,
+            replies_receiver,
+        })
+    }
+
+    /// Returns the local address the server is bound to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the address cannot be determined.
+    pub fn local_addr(&self) -> Result<SocketAddr, Error> {
+        Ok(self.udp_socket.local_addr()?)
+    }
+
+    /// Set the interval after which unacknowledged server messages are sent again.
+    ///
+    /// A message is sent up to five times.
+    #[must_use]
+    pub const fn with_retransmit_interval(mut self, retransmit_interval: Duration) -> Self {
+        self.retransmit_interval = retransmit_interval;
+        self
+    }
+
+    /// Returns a handle to push server messages to the logged-in clients.
+    #[must_use]
+    pub fn handle(&self) -> ServerHandle {
+        ServerHandle {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Serve clients until receiving on the socket fails.
+    ///
+    /// Each command is handled in a separate task, so that slow commands do not stall other clients.
+    /// Responses exceeding [`MAX_RESPONSE_SIZE`] are truncated.
+    /// Errors concerning single clients, such as failing to send to them or ICMP errors
+    /// reported by the socket, are logged and do not stop the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if receiving from the socket fails for reasons other than a single client.
+    pub async fn serve(mut self) -> Result<(), Error> {
+        let mut buffer = vec![0; BUF_SIZE];
+        let mut retransmit = interval(self.retransmit_interval);
+
+        loop {
+            select! {
+                result = self.udp_socket.recv_from(&mut buffer) => match result {
+                    Ok((size, address)) => {
+                        trace!("Received {size} bytes from {address}");
+                        self.receive(&buffer[..size], address).await;
+                    }
+                    Err(error) if is_client_error(&error) => {
+                        warn!("Ignoring error concerning a client: {error}");
+                    }
+                    Err(error) => return Err(error.into()),
+                },
+                Some(reply) = self.replies_receiver.recv() => self.reply(reply).await,
+                Some(message) = self.receiver.recv() => self.broadcast(message).await,
+                _ = retransmit.tick() => self.retransmit().await,
+            }
+        }
+    }
+
+    async fn receive(&mut self, datagram: &[u8], address: SocketAddr) {
+        let request = match Request::decode(datagram) {
+            Ok(request) => request,
+            Err(error) => {
+                warn!("Discarding invalid datagram from {address}: {error}");
+                return;
+            }
+        };
+
+        match request {
+            Request::Login(request) => {
+                let success = request.password() == self.password;
+
+                if success {
+                    info!("{address} logged in");
+                    self.sessions.insert(address, Session::new());
+                } else {
+                    warn!("{address} sent wrong password");
+                    self.sessions.remove(&address);
+                }
+
+                let datagram: Box<[u8]> = login::Response::new(success).into();
+                send(&self.udp_socket, &datagram, address).await;
+            }
+            Request::Command(request) => {
+                let Some(session) = self.sessions.get_mut(&address) else {
+                    debug!("Ignoring command from unauthenticated client {address}");
+                    return;
+                };
+
+                session.last_seen = Instant::now();
+                let seq = request.seq();
+
+                if let Some((last, datagrams)) = &session.last_response
+                    && *last == seq
+                {
+                    debug!("Repeating response #{seq} to {address}");
+
+                    for datagram in datagrams {
+                        send(&self.udp_socket, datagram, address).await;
+                    }
+                } else if session.pending.contains(&seq) {
+                    debug!("Command #{seq} from {address} is still being handled");
+                } else if request.payload().is_empty() {
+                    debug!("Received keepalive #{seq} from {address}");
+                    let datagrams = split(seq, &[]);
+
+                    for datagram in &datagrams {
+                        send(&self.udp_socket, datagram, address).await;
+                    }
+
+                    session.last_response = Some((seq, datagrams));
+                } else {
+                    debug!("Handling command #{seq} from {address}");
+                    session.pending.insert(seq);
+                    let handler = self.handler.clone();
+                    let replies = self.replies.clone();
+                    let command = request.payload().to_vec();
+
+                    spawn(async move {
+                        let payload = handler.handle(command).await;
+
+                        if replies
+                            .send(Reply {
+                                address,
+                                seq,
+                                payload,
+                            })
+                            .is_err()
+                        {
+                            debug!("Server stopped before responding to #{seq} from {address}");
+                        }
+                    });
+                }
+            }
+            Request::Ack(ack) => {
+                if let Some(session) = self.sessions.get_mut(&address) {
+                    debug!("{address} acknowledged message #{}", ack.seq());
+                    session.last_seen = Instant::now();
+                    session.unacknowledged.remove(&ack.seq());
+                }
+            }
+        }
+    }
+
+    async fn reply(&mut self, reply: Reply) {
+        let Reply {
+            address,
+            seq,
+            payload,
+        } = reply;
+
+        let Some(session) = self.sessions.get_mut(&address) else {
+            debug!("Discarding response #{seq} to {address}, whose session has ended");
+            return;
+        };
+
+        if !session.pending.remove(&seq) {
+            debug!("Discarding response #{seq} to {address}, who logged in again");
+            return;
+        }
+
+        let datagrams = split(seq, &payload);
+
+        for datagram in &datagrams {
+            send(&self.udp_socket, datagram, address).await;
+        }
+
+        session.last_response = Some((seq, datagrams));
+    }
+
+    async fn broadcast(&mut self, message: Vec<u8>) {
+        for (address, session) in &mut self.sessions {
+            let seq = session.next_seq;
+            session.next_seq = seq.wrapping_add(1);
+            let datagram: Box<[u8]> = server::Message::new(seq, message.clone()).into();
+            debug!("Sending message #{seq} to {address}");
+            send(&self.udp_socket, &datagram, *address).await;
+            session.unacknowledged.insert(seq, Pending::new(datagram));
+        }
+    }
+
+    async fn retransmit(&mut self) {
+        self.sessions.retain(|address, session| {
+            let alive = session.last_seen.elapsed() < SESSION_TIMEOUT;
+
+            if !alive {
+                info!("Session of {address} timed out");
+            }
+
+            alive
+        });
+
+        for (address, session) in &mut self.sessions {
+            session.unacknowledged.retain(|seq, pending| {
+                let exhausted = pending.attempts >= MAX_ATTEMPTS
+                    && pending.sent.elapsed() >= self.retransmit_interval;
+
+                if exhausted {
+                    warn!("{address} did not acknowledge message #{seq}");
+                }
+
+                !exhausted
+            });
+
+            for (seq, pending) in &mut session.unacknowledged {
+                if pending.sent.elapsed() >= self.retransmit_interval {
+                    debug!("Resending message #{seq} to {address}");
+                    send(&self.udp_socket, &pending.datagram, *address).await;
+                    pending.attempts += 1;
+                    pending.sent = Instant::now();
+                }
+            }
+        }
+    }
+}
+
+/// Handle to push server messages to the clients of a running [`Server`].
+#[derive(Clone, Debug)]
+pub struct ServerHandle {
+    sender: UnboundedSender<Vec<u8>>,
+}
+
+impl ServerHandle {
+    /// Send a server message to all logged-in clients.
+    ///
+    /// The message is sent again until each client acknowledged it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Disconnected`] if the server has been dropped.
+    pub fn broadcast<T>(&self, message: T) -> Result<(), Error>
+    where
+        T: Into<Vec<u8>>,
+    {
+        self.sender
+            .send(message.into())
+            .map_err(|_| Error::Disconnected)
+    }
+}
+
+/// The state of a logged-in client.
+#[derive(Debug)]
+struct Session {
+    last_seen: Instant,
+    last_response: Option<(u8, Vec<Box<[u8]>>)>,
+    pending: HashSet<u8>,
+    next_seq: u8,
+    unacknowledged: HashMap<u8, Pending>,
+}
+
+impl Session {
+    fn new() -> Self {
+        Self {
+            last_seen: Instant::now(),
+            last_response: None,
+            pending: HashSet::new(),
+            next_seq: 0,
+            unacknowledged: HashMap::new(),
+        }
+    }
+}
+
+/// The response of the handler to a client's command.
+#[derive(Debug)]
+struct Reply {
+    address: SocketAddr,
+    seq: u8,
+    payload: Vec<u8>,
+}
+
+/// A server message awaiting its acknowledgement.
+#[derive(Debug)]
+struct Pending {
+    datagram: Box<[u8]>,
+    sent: Instant,
+    attempts: u8,
+}
+
+impl Pending {
+    fn new(datagram: Box<[u8]>) -> Self {
+        Self {
+            datagram,
+            sent: Instant::now(),
+            attempts: 1,
+        }
+    }
+}
+
+/// Split a command response into datagrams.
+///
+/// Responses exceeding [`MAX_RESPONSE_SIZE`] are truncated.
+fn split(seq: u8, payload: &[u8]) -> Vec<Box<[u8]>> {
+    if payload.len() <= SPLIT_SIZE {
+        return vec![command::Response::new(seq, payload.to_vec()).into()];
+    }
+
+    let payload = if payload.len() > MAX_RESPONSE_SIZE {
+        warn!(
+            "Truncating response #{seq} of {} bytes to {MAX_RESPONSE_SIZE} bytes",
+            payload.len()
+        );
+        &payload[..MAX_RESPONSE_SIZE]
+    } else {
+        payload
+    };
+
+    let chunks: Vec<_> = payload.chunks(SPLIT_SIZE).collect();
+    let total = u8::try_from(chunks.len()).expect("Truncated response should fit into 255 parts.");
+
+    (0..total)
+        .zip(chunks)
+        .map(|(index, chunk)| {
+            command::Response::new(seq, [&[MULTIPART, total, index], chunk].concat()).into()
+        })
+        .collect()
+}
+
+async fn send(udp_socket: &UdpSocket, datagram: &[u8], address: SocketAddr) {
+    trace!("Sending bytes to {address}: {datagram:#04X?}");
+
+    if let Err(error) = udp_socket.send_to(datagram, address).await {
+        warn!("Sending to {address} failed: {error}");
+    }
+}
+
+/// Returns `true` if the error reported by the socket concerns a single client.
+///
+/// On Windows, receiving fails with [`ErrorKind::ConnectionReset`]
+/// after a datagram sent to a client was answered with an ICMP error.
+fn is_client_error(error: &std::io::Error) -> bool {
+    matches!(
+        error.kind(),
+        ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::HostUnreachable
+            | ErrorKind::NetworkUnreachable
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::net::UdpSocket;
+
+    use super::{MAX_RESPONSE_SIZE, Server, split};
+    use crate::battleye::Client;
+    use crate::battleye::packet::{Request, Response, login, server};
+    use crate::{Connect, Error, RCon};
+
+    async fn echo(command: Vec<u8>) -> Vec<u8> {
+        match command.as_slice() {
+            b"long" => vec![b'a'; 5000],
+            b"slow" => {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                command
+            }
+            _ => command,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server() {
+        let server = Server::bind("127.0.0.1:0", "secret", echo).await.unwrap();
+        let address = server.local_addr().unwrap();
+        let handle = server.handle();
+        tokio::spawn(server.serve());
+
+        let mut client = Client::builder()
+            .connect(address)
+            .await
+            .unwrap()
+            .login("secret")
+            .await
+            .unwrap();
+        let mut messages = client.subscribe();
+
+        assert_eq!(client.run_utf8("players").await.unwrap(), "players");
+        assert_eq!(client.run("long").await.unwrap(), vec![b'a'; 5000]);
+
+        handle.broadcast("Hello").unwrap();
+        assert_eq!(messages.recv().await.unwrap().text(), "Hello");
+    }
+
+    #[tokio::test]
+    async fn test_server_wrong_password() {
+        let server = Server::bind("127.0.0.1:0", "secret", echo).await.unwrap();
+        let address = server.local_addr().unwrap();
+        tokio::spawn(server.serve());
+
+        assert!(matches!(
+            Client::builder()
+                .connect(address)
+                .await
+                .unwrap()
+                .login("wrong")
+                .await,
+            Err(Error::AuthenticationFailed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_server_retransmits_messages() {
+        let server = Server::bind("127.0.0.1:0", "secret", echo)
+            .await
+            .unwrap()
+            .with_retransmit_interval(Duration::from_millis(20));
+        let address = server.local_addr().unwrap();
+        let handle = server.handle();
+        tokio::spawn(server.serve());
+
+        let udp_socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        udp_socket.connect(address).await.unwrap();
+        let login: Box<[u8]> = Request::Login(login::Request::from(b"secret".as_slice())).into();
+        udp_socket.send(&login).await.unwrap();
+        assert_eq!(
+            receive(&udp_socket).await,
+            Response::Login(login::Response::new(true))
+        );
+
+        handle.broadcast("Hello").unwrap();
+        let message = Response::Message(server::Message::new(0, b"Hello".to_vec()));
+        assert_eq!(receive(&udp_socket).await, message);
+        assert_eq!(receive(&udp_socket).await, message);
+    }
+
+    #[tokio::test]
+    async fn test_server_handles_commands_concurrently() {
+        let server = Server::bind("127.0.0.1:0", "secret", echo).await.unwrap();
+        let address = server.local_addr().unwrap();
+        tokio::spawn(server.serve());
+
+        let mut client = Client::builder()
+            .connect(address)
+            .await
+            .unwrap()
+            .login("secret")
+            .await
+            .unwrap();
+        let mut slow_client = client.clone();
+        tokio::spawn(async move { slow_client.run("slow").await });
+
+        assert_eq!(
+            tokio::time::timeout(Duration::from_secs(1), client.run_utf8("fast"))
+                .await
+                .unwrap()
+                .unwrap(),
+            "fast"
+        );
+    }
+
+    #[test]
+    fn test_split_truncates_long_responses() {
+        let datagrams = split(0, &vec![b'a'; MAX_RESPONSE_SIZE + 1]);
+        assert_eq!(datagrams.len(), usize::from(u8::MAX));
+
+        let payload: Vec<u8> = datagrams
+            .iter()
+            .map(|datagram| match Response::decode(datagram).unwrap() {
+                Response::Command(response) => response.payload()[3..].to_vec(),
+                response => panic!("Expected command response, got {response:?}"),
+            })
+            .collect::<Vec<_>>()
+            .concat();
+        assert_eq!(payload, vec![b'a'; MAX_RESPONSE_SIZE]);
+    }
+
+    async fn receive(udp_socket: &UdpSocket) -> Response {
+        let mut buffer = [0; 512];
+        let size = udp_socket.recv(&mut buffer).await.unwrap();
+        Response::decode(&buffer[..size]).unwrap()
+    }
+}
@@ -0,0 +1,52 @@
+use std::borrow::Cow;
+use std::time::SystemTime;
+
+/// A message pushed to the client by the server.
+///
+/// Server messages contain e.g. chat messages, player connects and disconnects
+/// or notifications about `RCon` admins logging in.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ServerMessage {
+    seq: u8,
+    timestamp: SystemTime,
+    payload: Vec<u8>,
+}
+
+impl ServerMessage {
+    /// Create a new server message.
+    #[must_use]
+    pub const fn new(seq: u8, timestamp: SystemTime, payload: Vec<u8>) -> Self {
+        Self {
+            seq,
+            timestamp,
+            payload,
+        }
+    }
+
+    /// The sequence number of the message.
+    #[must_use]
+    pub const fn seq(&self) -> u8 {
+        self.seq
+    }
+
+    /// The time at which the message was received.
+    #[must_use]
+    pub const fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// The raw bytes of the message.
+    #[must_use]
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// The message as text.
+    ///
+    /// Invalid UTF-8 sequences are replaced.
+    /// See [`String::from_utf8_lossy`] for details.
+    #[must_use]
+    pub fn text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.payload)
+    }
+}
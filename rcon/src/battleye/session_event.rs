@@ -0,0 +1,17 @@
+/// Events concerning the state of the session to the server.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SessionEvent {
+    /// The session to the server has been lost.
+    ///
+    /// Pending requests have been aborted.
+    Lost,
+    /// The client reconnected to the server and logged in again.
+    Reconnected {
+        /// The amount of attempts it took to reconnect.
+        attempts: u32,
+    },
+    /// The client gave up reconnecting to the server according to its retry policy.
+    ///
+    /// The client is unusable afterwards.
+    GaveUp,
+}
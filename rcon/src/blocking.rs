@@ -0,0 +1,135 @@
+//! Blocking clients on top of the standard library's sockets.
+//!
+//! These clients do not require an async runtime.
+//! They mirror the API of [`Connect`](crate::Connect) and [`RCon`](crate::RCon),
+//! but block the calling thread until the server responds or the timeout elapses.
+
+use std::net::ToSocketAddrs;
+use std::time::Duration;
+
+use crate::Error;
+
+#[cfg(any(feature = "battleye", feature = "source"))]
+pub(crate) mod util;
+
+/// Blocking client for the `BattlEye Rcon` protocol.
+#[cfg(feature = "battleye")]
+pub mod battleye {
+    pub use crate::battleye::blocking::{Client, Connection};
+}
+
+/// Blocking client for the `Source RCON` protocol.
+#[cfg(feature = "source")]
+pub mod source {
+    pub use crate::source::blocking::{Client, Connection};
+}
+
+/// Blocking API for connecting and logging in to `RCON` servers.
+///
+/// Commands can only be sent by the [`RCon`] client returned by a successful login.
+pub trait Connect: Sized {
+    /// The authenticated client returned after logging in.
+    type Client: RCon;
+
+    /// Connect to the specified address.
+    ///
+    /// The address may be a host name, such as `"mc.example.org:25575"`.
+    /// Each address it resolves to is tried in turn until a connection is established.
+    /// Clients of connectionless protocols only resolve the address here
+    /// and probe the resolved addresses when logging in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the address cannot be resolved or no connection could be established.
+    fn connect<T>(address: T) -> Result<Self, Error>
+    where
+        T: ToSocketAddrs;
+
+    /// Perform a login, consuming the connection.
+    ///
+    /// The login is subject to the connection's default timeout.
+    ///
+    /// # Returns
+    ///
+    /// Returns the authenticated client.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AuthenticationFailed`] if the server rejected the password.
+    /// Returns another [`Error`] if any I/O or protocol errors occurred.
+    /// If the server does not respond in time, [`Error::TimedOut`] is returned.
+    fn login<T>(self, password: T) -> Result<Self::Client, Error>
+    where
+        T: AsRef<[u8]>;
+}
+
+/// Blocking API for authenticated `RCON` protocol clients.
+pub trait RCon {
+    /// Run a command.
+    ///
+    /// The command is subject to the client's default timeout.
+    ///
+    /// # Returns
+    ///
+    /// Returns the raw bytes from the server's response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if any I/O or protocol errors occurred.
+    /// If the server does not respond in time, [`Error::TimedOut`] is returned.
+    fn run<T>(&mut self, command: T) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]>;
+
+    /// Run a command with a timeout overriding the client's default timeout.
+    ///
+    /// # Returns
+    ///
+    /// Returns the raw bytes from the server's response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if any I/O or protocol errors occurred.
+    /// If the server does not respond within `timeout`, [`Error::TimedOut`] is returned.
+    fn run_with_timeout<T>(&mut self, command: T, timeout: Duration) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]>;
+
+    /// Run a command.
+    ///
+    /// # Returns
+    ///
+    /// Returns a valid UTF-8 string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if any I/O or protocol errors occurred or if the returned bytes are not valid UTF-8.
+    fn run_utf8<T>(&mut self, command: T) -> Result<String, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        Ok(String::from_utf8(self.run(command)?)?)
+    }
+
+    /// Run a command.
+    ///
+    /// # Returns
+    ///
+    /// Returns a valid UTF-8 string that may be truncated.
+    ///
+    /// This command will not error when the returned bytes contain
+    /// invalid UTF-8 bytes, but will replace them accordingly.
+    ///
+    /// See [`String::from_utf8_lossy`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if any I/O or protocol errors occurred.
+    fn run_utf8_lossy<T>(&mut self, command: T) -> Result<String, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.run(command)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
@@ -0,0 +1,108 @@
+//! Helpers shared by the blocking clients.
+
+use std::io::ErrorKind;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use log::{info, warn};
+
+use crate::Error;
+
+/// Default timeout for connecting, logging in and running commands.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Resolve the given address to its socket addresses.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if resolution fails or yields no socket addresses.
+pub fn resolve<A>(address: A) -> Result<Vec<SocketAddr>, Error>
+where
+    A: ToSocketAddrs,
+{
+    let addresses: Vec<_> = address.to_socket_addrs()?.collect();
+
+    if addresses.is_empty() {
+        return Err(unresolved());
+    }
+
+    Ok(addresses)
+}
+
+/// Resolve the given address and try to connect to each resolved socket address in turn.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if resolution fails,
+/// or the error of the last connection attempt if no connection could be established.
+pub fn connect_any<A, F, T>(address: A, mut connect: F) -> Result<T, Error>
+where
+    A: ToSocketAddrs,
+    F: FnMut(SocketAddr) -> Result<T, Error>,
+{
+    let mut last_error = None;
+
+    for address in resolve(address)? {
+        match connect(address) {
+            Ok(connection) => {
+                info!("Connected to {address}");
+                return Ok(connection);
+            }
+            Err(error) => {
+                warn!("Connecting to {address} failed: {error}");
+                last_error.replace(error);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(unresolved))
+}
+
+/// The point in time by which an operation must be completed.
+#[derive(Clone, Copy, Debug)]
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+    /// Create a deadline after the given timeout.
+    ///
+    /// If `None`, the deadline never passes.
+    pub fn after(timeout: Option<Duration>) -> Self {
+        Self(timeout.map(|timeout| Instant::now() + timeout))
+    }
+
+    /// Returns the time remaining until the deadline to be used as a socket timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TimedOut`] if the deadline has passed.
+    pub fn remaining(self) -> Result<Option<Duration>, Error> {
+        let Some(deadline) = self.0 else {
+            return Ok(None);
+        };
+
+        match deadline.checked_duration_since(Instant::now()) {
+            Some(remaining) if !remaining.is_zero() => Ok(Some(remaining)),
+            _ => Err(Error::TimedOut),
+        }
+    }
+}
+
+fn unresolved() -> Error {
+    Error::Io(std::io::Error::new(
+        ErrorKind::NotFound,
+        "Address did not resolve to any socket address",
+    ))
+}
+
+/// Checks whether the I/O error was caused by an elapsed socket timeout.
+pub fn is_timeout(error: &std::io::Error) -> bool {
+    matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+/// Maps errors caused by an elapsed socket timeout to [`Error::TimedOut`].
+pub fn timed_out(error: Error) -> Error {
+    match error {
+        Error::Io(error) if is_timeout(&error) => Error::TimedOut,
+        other => other,
+    }
+}
@@ -8,11 +8,16 @@ use std::time::Duration;
 use log::warn;
 
 pub use self::banning::{BanListEntry, Error, SECS_PER_MINUTE, Target};
+pub use self::event::{Channel, Event};
 pub use self::player::Player;
 use crate::RCon;
 use crate::battleye::BattlEye;
 
 mod banning;
+mod event;
+mod fields;
+#[cfg(feature = "server")]
+pub mod mock;
 mod player;
 
 const BROADCAST_TARGET: i64 = -1;
@@ -24,12 +29,12 @@ pub trait DayZ: RCon + BattlEye {
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if sending the message fails.
+    /// Returns a [`crate::Error`] if sending the message fails.
     fn say<T>(
         &mut self,
         index: u64,
         message: T,
-    ) -> impl Future<Output = std::io::Result<()>> + Send
+    ) -> impl Future<Output = Result<(), crate::Error>> + Send
     where
         T: AsRef<str> + Send;
 
@@ -37,8 +42,8 @@ pub trait DayZ: RCon + BattlEye {
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if sending the message fails.
-    fn broadcast<T>(&mut self, message: T) -> impl Future<Output = std::io::Result<()>> + Send
+    /// Returns a [`crate::Error`] if sending the message fails.
+    fn broadcast<T>(&mut self, message: T) -> impl Future<Output = Result<(), crate::Error>> + Send
     where
         T: AsRef<str> + Send;
 
@@ -48,12 +53,12 @@ pub trait DayZ: RCon + BattlEye {
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if kicking the player fails.
+    /// Returns a [`crate::Error`] if kicking the player fails.
     fn kick<T>(
         &mut self,
         index: u64,
         reason: Option<T>,
-    ) -> impl Future<Output = std::io::Result<()>> + Send
+    ) -> impl Future<Output = Result<(), crate::Error>> + Send
     where
         T: AsRef<str> + Send;
 
@@ -63,12 +68,12 @@ pub trait DayZ: RCon + BattlEye {
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if banning  the player fails.
+    /// Returns a [`crate::Error`] if banning  the player fails.
     fn ban<T>(
         &mut self,
         index: u64,
         reason: Option<T>,
-    ) -> impl Future<Output = std::io::Result<()>> + Send
+    ) -> impl Future<Output = Result<(), crate::Error>> + Send
     where
         T: AsRef<str> + Send;
 
@@ -76,7 +81,7 @@ pub trait DayZ: RCon + BattlEye {
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if querying the ban list fails.
+    /// Returns a [`crate::Error`] if querying the ban list fails.
     fn bans(&mut self) -> impl Future<Output = Result<Vec<BanListEntry>, crate::Error>> + Send;
 
     /// Add an entry to the ban list.
@@ -87,7 +92,7 @@ pub trait DayZ: RCon + BattlEye {
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if banning  the player fails.
+    /// Returns a [`crate::Error`] if banning  the player fails.
     fn add_ban<T>(
         &mut self,
         target: Target,
@@ -101,14 +106,14 @@ pub trait DayZ: RCon + BattlEye {
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if unbanning  the player fails.
-    fn remove_ban(&mut self, index: u64) -> impl Future<Output = std::io::Result<()>> + Send;
+    /// Returns a [`crate::Error`] if unbanning  the player fails.
+    fn remove_ban(&mut self, index: u64) -> impl Future<Output = Result<(), crate::Error>> + Send;
 
     /// List players on the server.
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if listing the players fails.
+    /// Returns a [`crate::Error`] if listing the players fails.
     fn players(&mut self) -> impl Future<Output = Result<Vec<Player>, crate::Error>> + Send;
 
     /// Lock the server.
@@ -117,8 +122,8 @@ pub trait DayZ: RCon + BattlEye {
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if any I/O error occurred.
-    fn lock(&mut self) -> impl Future<Output = std::io::Result<()>> + Send;
+    /// Returns a [`crate::Error`] if any I/O error occurred.
+    fn lock(&mut self) -> impl Future<Output = Result<(), crate::Error>> + Send;
 
     /// Unlock the server.
     ///
@@ -126,29 +131,29 @@ pub trait DayZ: RCon + BattlEye {
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if any I/O error occurred.
-    fn unlock(&mut self) -> impl Future<Output = std::io::Result<()>> + Send;
+    /// Returns a [`crate::Error`] if any I/O error occurred.
+    fn unlock(&mut self) -> impl Future<Output = Result<(), crate::Error>> + Send;
 
     /// Shutdown the server immediately.
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if any I/O error occurred.
-    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> + Send;
+    /// Returns a [`crate::Error`] if any I/O error occurred.
+    fn shutdown(&mut self) -> impl Future<Output = Result<(), crate::Error>> + Send;
 
     /// Reload server config file loaded by -config option.
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if any I/O error occurred.
-    fn reload(&mut self) -> impl Future<Output = std::io::Result<()>> + Send;
+    /// Returns a [`crate::Error`] if any I/O error occurred.
+    fn reload(&mut self) -> impl Future<Output = Result<(), crate::Error>> + Send;
 }
 
 impl<T> DayZ for T
 where
     T: RCon + BattlEye + Send,
 {
-    async fn say<U>(&mut self, index: u64, message: U) -> std::io::Result<()>
+    async fn say<U>(&mut self, index: u64, message: U) -> Result<(), crate::Error>
     where
         U: AsRef<str> + Send,
     {
@@ -157,7 +162,7 @@ where
             .map(drop)
     }
 
-    async fn broadcast<U>(&mut self, message: U) -> std::io::Result<()>
+    async fn broadcast<U>(&mut self, message: U) -> Result<(), crate::Error>
     where
         U: AsRef<str> + Send,
     {
@@ -166,7 +171,7 @@ where
             .map(drop)
     }
 
-    async fn kick<U>(&mut self, index: u64, reason: Option<U>) -> std::io::Result<()>
+    async fn kick<U>(&mut self, index: u64, reason: Option<U>) -> Result<(), crate::Error>
     where
         U: AsRef<str> + Send,
     {
@@ -179,7 +184,7 @@ where
         self.run(args.join(" ")).await.map(drop)
     }
 
-    async fn ban<U>(&mut self, index: u64, reason: Option<U>) -> std::io::Result<()>
+    async fn ban<U>(&mut self, index: u64, reason: Option<U>) -> Result<(), crate::Error>
     where
         U: AsRef<str> + Send,
     {
@@ -242,7 +247,7 @@ where
         }
     }
 
-    async fn remove_ban(&mut self, id: u64) -> std::io::Result<()> {
+    async fn remove_ban(&mut self, id: u64) -> Result<(), crate::Error> {
         self.run(format!("removeBan {id}")).await.map(drop)
     }
 
@@ -264,19 +269,19 @@ where
         })
     }
 
-    async fn lock(&mut self) -> std::io::Result<()> {
+    async fn lock(&mut self) -> Result<(), crate::Error> {
         self.run("#lock").await.map(drop)
     }
 
-    async fn unlock(&mut self) -> std::io::Result<()> {
+    async fn unlock(&mut self) -> Result<(), crate::Error> {
         self.run("#unlock").await.map(drop)
     }
 
-    async fn shutdown(&mut self) -> std::io::Result<()> {
+    async fn shutdown(&mut self) -> Result<(), crate::Error> {
         self.run("#shutdown").await.map(drop)
     }
 
-    async fn reload(&mut self) -> std::io::Result<()> {
+    async fn reload(&mut self) -> Result<(), crate::Error> {
         self.run("#init").await.map(drop)
     }
 }
@@ -6,6 +6,8 @@ use std::time::Duration;
 pub use error::Error;
 pub use target::Target;
 
+use super::fields::split_field;
+
 mod error;
 mod target;
 
@@ -14,7 +16,7 @@ pub const PERM_BAN: &str = "perm";
 pub const SECS_PER_MINUTE: u64 = 60;
 
 /// A ban list entry.
-#[derive(Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BanListEntry {
     index: u64,
     target: Target,
@@ -23,6 +25,22 @@ pub struct BanListEntry {
 }
 
 impl BanListEntry {
+    /// Create a new ban list entry.
+    #[must_use]
+    pub const fn new(
+        index: u64,
+        target: Target,
+        duration: Option<Duration>,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            index,
+            target,
+            duration,
+            reason,
+        }
+    }
+
     /// The index of the ban list entry.
     #[must_use]
     pub const fn index(&self) -> u64 {
@@ -83,18 +101,16 @@ impl FromStr for BanListEntry {
     type Err = Cow<'static, str>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut fields = s.split_whitespace();
-
-        let id = fields.next().ok_or("Missing ID field")?;
+        let (id, rest) = split_field(s).ok_or("Missing ID field")?;
         let id: u64 = id
             .parse()
             .map_err(|_| format!("Invalid u64 for ID: {id}"))?;
 
-        let target = fields.next().ok_or("Missing ban target field")?;
+        let (target, rest) = split_field(rest).ok_or("Missing ban target field")?;
         let target =
             Target::from_str(target).map_err(|()| format!("Invalid ban type: {target}"))?;
 
-        let duration = fields.next().ok_or("Missing duration field")?;
+        let (duration, reason) = split_field(rest).ok_or("Missing duration field")?;
         let duration = if duration == PERM_BAN {
             None
         } else if duration == "-" {
@@ -105,7 +121,7 @@ impl FromStr for BanListEntry {
                 .map_err(|_| format!(r#"Invalid duration: "{duration}""#))?
         };
 
-        let reason = fields.next().map(ToString::to_string);
+        let reason = (!reason.is_empty()).then(|| reason.to_string());
 
         Ok(Self {
             index: id,
@@ -115,3 +131,59 @@ impl FromStr for BanListEntry {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use std::time::Duration;
+
+    use super::{BanListEntry, SECS_PER_MINUTE, Target};
+
+    #[test]
+    fn test_parse_guid_ban() {
+        let entry: BanListEntry =
+            "0  0123456789abcdef0123456789abcdef perm Cheating is not allowed"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            entry,
+            BanListEntry::new(
+                0,
+                Target::Uuid("0123456789abcdef0123456789abcdef".parse().unwrap()),
+                None,
+                Some("Cheating is not allowed".to_string()),
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_ip_ban() {
+        let entry: BanListEntry = "1  10.0.0.1        59   Spamming the chat".parse().unwrap();
+        assert_eq!(
+            entry,
+            BanListEntry::new(
+                1,
+                Target::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+                Some(Duration::from_secs(59 * SECS_PER_MINUTE)),
+                Some("Spamming the chat".to_string()),
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_expired_ban_without_reason() {
+        let entry: BanListEntry = "2  10.0.0.2        -".parse().unwrap();
+        assert_eq!(entry.duration(), Some(Duration::ZERO));
+        assert_eq!(entry.reason(), None);
+    }
+
+    #[test]
+    fn test_parse_invalid_ban() {
+        assert!(
+            "[#] [GUID] [Minutes left] [Reason]"
+                .parse::<BanListEntry>()
+                .is_err()
+        );
+        assert!("3  10.0.0.3".parse::<BanListEntry>().is_err());
+    }
+}
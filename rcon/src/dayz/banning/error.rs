@@ -1,12 +1,15 @@
 use std::fmt::{Display, Formatter};
 use std::io;
 
-/// Represents either an I/O error or an invalid ban format.
+/// Represents either an I/O error, an `RCON` error or an invalid ban format.
 #[derive(Debug)]
 pub enum Error {
     /// An I/O error occurred.
     Io(io::Error),
 
+    /// An `RCON` protocol error occurred.
+    Rcon(crate::Error),
+
     /// An invalid ban format was provided.
     InvalidBanFormat,
 }
@@ -15,6 +18,7 @@ impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Io(error) => error.fmt(f),
+            Self::Rcon(error) => error.fmt(f),
             Self::InvalidBanFormat => write!(f, "Invalid ban format"),
         }
     }
@@ -24,6 +28,7 @@ impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::Io(error) => Some(error),
+            Self::Rcon(error) => Some(error),
             Self::InvalidBanFormat => None,
         }
     }
@@ -35,10 +40,20 @@ impl From<io::Error> for Error {
     }
 }
 
+impl From<crate::Error> for Error {
+    fn from(error: crate::Error) -> Self {
+        match error {
+            crate::Error::Io(error) => Self::Io(error),
+            error => Self::Rcon(error),
+        }
+    }
+}
+
 impl From<Error> for crate::Error {
     fn from(error: Error) -> Self {
         match error {
             Error::Io(error) => Self::Io(error),
+            Error::Rcon(error) => error,
             Error::InvalidBanFormat => Self::Io(io::Error::other("Invalid ban format")),
         }
     }
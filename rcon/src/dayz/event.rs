@@ -0,0 +1,363 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+pub use channel::Channel;
+use uuid::Uuid;
+
+use super::fields::{parse_guid, split_field, split_parenthesized};
+use crate::battleye::ServerMessage;
+
+mod channel;
+
+const ADMIN_BAN: &str = "Admin Ban";
+const RESTRICTION: &str = " Restriction #";
+
+/// An event on a `DayZ` server, as reported by server messages.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Event {
+    /// A player connected to the server.
+    PlayerConnected {
+        /// The index of the player on the server.
+        index: u64,
+        /// The player's name.
+        name: String,
+        /// The socket address from which the player connected.
+        socket_addr: SocketAddr,
+    },
+    /// The GUID of a player has been verified.
+    GuidVerified {
+        /// The index of the player on the server.
+        index: u64,
+        /// The player's name.
+        name: String,
+        /// The player's globally unique identifier.
+        guid: Uuid,
+    },
+    /// A player disconnected from the server.
+    PlayerDisconnected {
+        /// The index of the player on the server.
+        index: u64,
+        /// The player's name.
+        name: String,
+    },
+    /// A player has been kicked from the server.
+    PlayerKicked {
+        /// The index of the player on the server.
+        index: u64,
+        /// The player's name.
+        name: String,
+        /// The player's globally unique identifier.
+        guid: Uuid,
+        /// The reason for the kick.
+        reason: String,
+    },
+    /// A player has been banned from the server.
+    PlayerBanned {
+        /// The index of the player on the server.
+        index: u64,
+        /// The player's name.
+        name: String,
+        /// The player's globally unique identifier.
+        guid: Uuid,
+        /// The reason for the ban.
+        reason: String,
+    },
+    /// A player has been kicked for violating a `BattlEye` filter.
+    FilterViolation {
+        /// The index of the player on the server.
+        index: u64,
+        /// The player's name.
+        name: String,
+        /// The player's globally unique identifier.
+        guid: Uuid,
+        /// The name of the violated filter, e.g. `Script`.
+        filter: String,
+        /// The number of the violated restriction within the filter.
+        restriction: u64,
+    },
+    /// A chat message.
+    Chat {
+        /// The channel on which the message was sent.
+        channel: Channel,
+        /// The name of the sender.
+        sender: String,
+        /// The message text.
+        message: String,
+    },
+    /// An `RCon` admin logged in.
+    AdminLogin {
+        /// The index of the admin.
+        index: u64,
+        /// The socket address from which the admin connected.
+        socket_addr: SocketAddr,
+    },
+    /// A message that could not be parsed into any of the above events.
+    Unknown(String),
+}
+
+impl From<&ServerMessage> for Event {
+    fn from(message: &ServerMessage) -> Self {
+        Self::from(message.text().as_ref())
+    }
+}
+
+impl From<&str> for Event {
+    fn from(text: &str) -> Self {
+        parse_player_event(text)
+            .or_else(|| parse_guid_verified(text))
+            .or_else(|| parse_admin_event(text))
+            .or_else(|| parse_chat(text))
+            .unwrap_or_else(|| Self::Unknown(text.to_string()))
+    }
+}
+
+impl FromStr for Event {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+/// Parse messages of the form `Player #<index> <name> ...`.
+fn parse_player_event(text: &str) -> Option<Event> {
+    let (index, rest) = split_field(text.strip_prefix("Player #")?)?;
+    let index = index.parse().ok()?;
+
+    if let Some(name) = rest.strip_suffix(" disconnected") {
+        return Some(Event::PlayerDisconnected {
+            index,
+            name: name.to_string(),
+        });
+    }
+
+    if let Some(rest) = rest.strip_suffix(" connected") {
+        let (name, socket_addr) = split_parenthesized(rest)?;
+        return Some(Event::PlayerConnected {
+            index,
+            name: name.to_string(),
+            socket_addr: socket_addr.parse().ok()?,
+        });
+    }
+
+    let (rest, reason) = rest.split_once(" has been kicked by BattlEye: ")?;
+    let (name, guid) = split_parenthesized(rest)?;
+    let name = name.to_string();
+    let guid = parse_guid(guid).ok()?;
+
+    if reason.starts_with(ADMIN_BAN) {
+        return Some(Event::PlayerBanned {
+            index,
+            name,
+            guid,
+            reason: reason.to_string(),
+        });
+    }
+
+    if let Some((filter, restriction)) = reason.split_once(RESTRICTION)
+        && let Ok(restriction) = restriction.parse()
+    {
+        return Some(Event::FilterViolation {
+            index,
+            name,
+            guid,
+            filter: filter.to_string(),
+            restriction,
+        });
+    }
+
+    Some(Event::PlayerKicked {
+        index,
+        name,
+        guid,
+        reason: reason.to_string(),
+    })
+}
+
+/// Parse messages of the form `Verified GUID (<guid>) of player #<index> <name>`.
+fn parse_guid_verified(text: &str) -> Option<Event> {
+    let (guid, rest) = text
+        .strip_prefix("Verified GUID (")?
+        .split_once(") of player #")?;
+    let (index, name) = split_field(rest)?;
+    Some(Event::GuidVerified {
+        index: index.parse().ok()?,
+        name: name.to_string(),
+        guid: parse_guid(guid).ok()?,
+    })
+}
+
+/// Parse messages of the form `RCon admin #<index> ...`.
+fn parse_admin_event(text: &str) -> Option<Event> {
+    let rest = text.strip_prefix("RCon admin #")?;
+
+    if let Some((index, message)) = rest.split_once(": ") {
+        return Some(Event::Chat {
+            channel: Channel::Admin,
+            sender: format!("RCon admin #{index}"),
+            message: message.to_string(),
+        });
+    }
+
+    let (index, socket_addr) = split_parenthesized(rest.strip_suffix(" logged in")?)?;
+    Some(Event::AdminLogin {
+        index: index.parse().ok()?,
+        socket_addr: socket_addr.parse().ok()?,
+    })
+}
+
+/// Parse messages of the form `(<channel>) <sender>: <message>`.
+fn parse_chat(text: &str) -> Option<Event> {
+    let (channel, rest) = text.strip_prefix('(')?.split_once(") ")?;
+    let (sender, message) = rest.split_once(": ")?;
+    Some(Event::Chat {
+        channel: channel.parse().ok()?,
+        sender: sender.to_string(),
+        message: message.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use uuid::Uuid;
+
+    use super::{Channel, Event};
+
+    const GUID: &str = "0123456789abcdef0123456789abcdef";
+
+    fn guid() -> Uuid {
+        GUID.parse().expect("GUID should be valid")
+    }
+
+    #[test]
+    fn test_player_connected() {
+        assert_eq!(
+            Event::from("Player #3 John Doe (192.168.0.42:2304) connected"),
+            Event::PlayerConnected {
+                index: 3,
+                name: "John Doe".to_string(),
+                socket_addr: SocketAddr::from(([192, 168, 0, 42], 2304)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_guid_verified() {
+        assert_eq!(
+            Event::from(format!("Verified GUID ({GUID}) of player #3 John Doe").as_str()),
+            Event::GuidVerified {
+                index: 3,
+                name: "John Doe".to_string(),
+                guid: guid(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_player_disconnected() {
+        assert_eq!(
+            Event::from("Player #3 John Doe disconnected"),
+            Event::PlayerDisconnected {
+                index: 3,
+                name: "John Doe".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_player_kicked() {
+        assert_eq!(
+            Event::from(
+                format!(
+                    "Player #3 John Doe ({GUID}) has been kicked by BattlEye: Admin Kick (AFK)"
+                )
+                .as_str()
+            ),
+            Event::PlayerKicked {
+                index: 3,
+                name: "John Doe".to_string(),
+                guid: guid(),
+                reason: "Admin Kick (AFK)".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_player_banned() {
+        assert_eq!(
+            Event::from(
+                format!(
+                    "Player #3 John Doe ({GUID}) has been kicked by BattlEye: Admin Ban (Cheating)"
+                )
+                .as_str()
+            ),
+            Event::PlayerBanned {
+                index: 3,
+                name: "John Doe".to_string(),
+                guid: guid(),
+                reason: "Admin Ban (Cheating)".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_filter_violation() {
+        assert_eq!(
+            Event::from(
+                format!("Player #3 John Doe ({GUID}) has been kicked by BattlEye: Script Restriction #42")
+                    .as_str()
+            ),
+            Event::FilterViolation {
+                index: 3,
+                name: "John Doe".to_string(),
+                guid: guid(),
+                filter: "Script".to_string(),
+                restriction: 42,
+            }
+        );
+    }
+
+    #[test]
+    fn test_chat() {
+        assert_eq!(
+            Event::from("(Side) John Doe: Hello: World"),
+            Event::Chat {
+                channel: Channel::Side,
+                sender: "John Doe".to_string(),
+                message: "Hello: World".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_admin_chat() {
+        assert_eq!(
+            Event::from("RCon admin #1: (Global) Server restart in 5 minutes"),
+            Event::Chat {
+                channel: Channel::Admin,
+                sender: "RCon admin #1".to_string(),
+                message: "(Global) Server restart in 5 minutes".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_admin_login() {
+        assert_eq!(
+            Event::from("RCon admin #1 (127.0.0.1:57012) logged in"),
+            Event::AdminLogin {
+                index: 1,
+                socket_addr: SocketAddr::from(([127, 0, 0, 1], 57012)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_unknown() {
+        let text = "Player #3 John Doe - Legacy GUID: 1234";
+        assert_eq!(Event::from(text), Event::Unknown(text.to_string()));
+    }
+}
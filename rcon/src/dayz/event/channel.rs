@@ -0,0 +1,55 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A chat channel.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Channel {
+    /// The global chat channel.
+    Global,
+    /// The side chat channel.
+    Side,
+    /// The direct chat channel.
+    Direct,
+    /// Messages sent by `RCon` admins.
+    Admin,
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Global => write!(f, "Global"),
+            Self::Side => write!(f, "Side"),
+            Self::Direct => write!(f, "Direct"),
+            Self::Admin => write!(f, "Admin"),
+        }
+    }
+}
+
+impl FromStr for Channel {
+    type Err = Cow<'static, str>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Global" => Ok(Self::Global),
+            "Side" => Ok(Self::Side),
+            "Direct" => Ok(Self::Direct),
+            "Admin" => Ok(Self::Admin),
+            _ => Err(format!("invalid channel: {s}").into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Channel;
+
+    #[test]
+    fn test_parse_channel() {
+        assert_eq!("Side".parse(), Ok(Channel::Side));
+        assert_eq!(
+            "Vehicle".parse::<Channel>(),
+            Err("invalid channel: Vehicle".into())
+        );
+    }
+}
@@ -0,0 +1,50 @@
+//! Helpers for parsing the text output of `DayZ` servers.
+
+use uuid::Uuid;
+
+/// Split off the first whitespace-separated field of the text.
+///
+/// Returns the field and the remaining text without surrounding whitespace,
+/// or `None` if the text contains no field.
+pub fn split_field(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_start();
+
+    if text.is_empty() {
+        return None;
+    }
+
+    Some(
+        text.split_once(char::is_whitespace)
+            .map_or((text, ""), |(field, rest)| (field, rest.trim())),
+    )
+}
+
+/// Split `<head> (<tail>)` into `<head>` and `<tail>`.
+pub fn split_parenthesized(text: &str) -> Option<(&str, &str)> {
+    text.strip_suffix(')')?.rsplit_once(" (")
+}
+
+/// Parse a GUID, ignoring a trailing verification status such as `(OK)` or `(?)`.
+pub fn parse_guid(text: &str) -> Result<Uuid, uuid::Error> {
+    text.split_once('(').map_or(text, |(guid, _)| guid).parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_guid, split_field};
+
+    #[test]
+    fn test_split_field() {
+        assert_eq!(split_field("  0   John  Doe \r"), Some(("0", "John  Doe")));
+        assert_eq!(split_field("0"), Some(("0", "")));
+        assert_eq!(split_field(" \t"), None);
+    }
+
+    #[test]
+    fn test_parse_guid() {
+        let guid = "0123456789abcdef0123456789abcdef";
+        assert_eq!(parse_guid(&format!("{guid}(OK)")), guid.parse());
+        assert_eq!(parse_guid(&format!("{guid}(?)")), guid.parse());
+        assert_eq!(parse_guid(guid), guid.parse());
+    }
+}
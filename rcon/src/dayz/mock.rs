@@ -0,0 +1,483 @@
+//! A mock `DayZ` server for testing `BattlEye Rcon` clients.
+//!
+//! The mock keeps an in-memory player list and ban list
+//! and answers the commands used by the [`DayZ`](crate::DayZ) trait in the format of a real server.
+
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use tokio::net::ToSocketAddrs;
+
+use super::{BROADCAST_TARGET, BanListEntry, INVALID_BAN_FORMAT_MESSAGE, Player, Target};
+use crate::Error;
+use crate::battleye::server::{Handler, Server, ServerHandle};
+use crate::dayz::SECS_PER_MINUTE;
+use crate::dayz::banning::PERM_BAN;
+
+const UNKNOWN_COMMAND_MESSAGE: &str = "Unknown command";
+
+/// A mock `DayZ` server.
+///
+/// Clones share the same state, so that the state can be inspected
+/// while a clone handles the commands of a [`Server`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use rcon::dayz::mock::MockServer;
+///
+/// # async fn serve() -> Result<(), rcon::Error> {
+/// let mock = MockServer::new();
+/// let server = mock.bind("127.0.0.1:2302", "secret").await?;
+/// tokio::spawn(server.serve());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MockServer {
+    state: Arc<Mutex<State>>,
+}
+
+impl MockServer {
+    /// Create a mock server without players and bans.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a player to the server.
+    #[must_use]
+    pub fn with_player(self, player: Player) -> Self {
+        self.state().players.push(player);
+        self
+    }
+
+    /// Add an entry to the ban list.
+    ///
+    /// A duration of `None` indicates a permanent ban.
+    #[must_use]
+    pub fn with_ban(
+        self,
+        target: Target,
+        duration: Option<Duration>,
+        reason: Option<&str>,
+    ) -> Self {
+        self.state().add(Ban {
+            target,
+            minutes: duration.map(|duration| duration.as_secs() / SECS_PER_MINUTE),
+            reason: reason.map(ToString::to_string),
+        });
+        self
+    }
+
+    /// Bind a `BattlEye Rcon` server serving this mock to the given address.
+    ///
+    /// Events such as kicks and bans are sent to the logged-in clients as server messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the socket cannot be bound.
+    pub async fn bind<A, P>(&self, address: A, password: P) -> Result<Server<Self>, Error>
+    where
+        A: ToSocketAddrs,
+        P: AsRef<[u8]>,
+    {
+        let server = Server::bind(address, password, self.clone()).await?;
+        self.state().server.replace(server.handle());
+        Ok(server)
+    }
+
+    /// Returns the players currently on the server.
+    #[must_use]
+    pub fn players(&self) -> Vec<Player> {
+        self.state().players.clone()
+    }
+
+    /// Returns the current ban list.
+    #[must_use]
+    pub fn bans(&self) -> Vec<BanListEntry> {
+        (0..)
+            .zip(&self.state().bans)
+            .map(|(index, ban)| {
+                BanListEntry::new(
+                    index,
+                    ban.target,
+                    ban.minutes
+                        .map(|minutes| Duration::from_secs(minutes * SECS_PER_MINUTE)),
+                    ban.reason.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the server is locked.
+    #[must_use]
+    pub fn is_locked(&self) -> bool {
+        self.state().locked
+    }
+
+    /// Returns `true` if the server has been shut down.
+    #[must_use]
+    pub fn is_shut_down(&self) -> bool {
+        self.state().shut_down
+    }
+
+    /// Returns how often the server config has been reloaded.
+    #[must_use]
+    pub fn reloads(&self) -> usize {
+        self.state().reloads
+    }
+
+    /// Run a command and return the server's response.
+    #[must_use]
+    pub fn run(&self, command: &str) -> String {
+        self.state().run(command)
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Handler for MockServer {
+    async fn handle(&self, command: Vec<u8>) -> Vec<u8> {
+        self.run(&String::from_utf8_lossy(&command)).into_bytes()
+    }
+}
+
+/// The mutable state of a [`MockServer`].
+#[derive(Debug, Default)]
+struct State {
+    players: Vec<Player>,
+    bans: Vec<Ban>,
+    locked: bool,
+    shut_down: bool,
+    reloads: usize,
+    server: Option<ServerHandle>,
+}
+
+impl State {
+    fn run(&mut self, command: &str) -> String {
+        let (name, args) = command.split_once(' ').unwrap_or((command, ""));
+
+        match name {
+            "players" => self.list_players(),
+            "bans" => self.list_bans(),
+            "addBan" => self.add_ban(args),
+            "removeBan" => {
+                if let Some(index) = args
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|&index| index < self.bans.len())
+                {
+                    self.bans.remove(index);
+                }
+
+                String::new()
+            }
+            "kick" => {
+                let (index, reason) = split_index(args);
+                self.kick(index, "Admin Kick", reason);
+                String::new()
+            }
+            "ban" => {
+                let (index, reason) = split_index(args);
+
+                if let Some(player) = self.kick(index, "Admin Ban", reason) {
+                    self.add(Ban {
+                        target: Target::Uuid(player.guid()),
+                        minutes: None,
+                        reason: reason.map(ToString::to_string),
+                    });
+                }
+
+                String::new()
+            }
+            "say" => {
+                let (index, message) = args.split_once(' ').unwrap_or((args, ""));
+
+                if index.parse() == Ok(BROADCAST_TARGET) {
+                    self.notify(format!("RCon admin #0: (Global) {message}"));
+                }
+
+                String::new()
+            }
+            "#lock" => {
+                self.locked = true;
+                String::new()
+            }
+            "#unlock" => {
+                self.locked = false;
+                String::new()
+            }
+            "#shutdown" => {
+                self.shut_down = true;
+                self.players.clear();
+                String::new()
+            }
+            "#init" => {
+                self.reloads += 1;
+                String::new()
+            }
+            _ => UNKNOWN_COMMAND_MESSAGE.to_string(),
+        }
+    }
+
+    fn list_players(&self) -> String {
+        let mut text = String::from(
+            "Players on server:\n\
+             [#] [IP Address]:[Port] [Ping] [GUID] [Name]\n\
+             --------------------------------------------------\n",
+        );
+
+        for player in &self.players {
+            let _ = writeln!(
+                text,
+                "{:<3} {:<21} {:<4} {}(OK) {}",
+                player.index(),
+                player.socket_addr(),
+                player.ping().as_millis(),
+                player.guid().simple(),
+                player.name()
+            );
+        }
+
+        let _ = write!(text, "({} players in total)", self.players.len());
+        text
+    }
+
+    fn list_bans(&self) -> String {
+        let mut text = String::from(
+            "GUID Bans:\n\
+             [#] [GUID] [Minutes left] [Reason]\n\
+             ----------------------------------------\n",
+        );
+
+        for (index, ban) in self.bans.iter().enumerate() {
+            if let Target::Uuid(uuid) = ban.target {
+                let _ = writeln!(text, "{index:<3} {} {}", uuid.simple(), ban.details());
+            }
+        }
+
+        text.push_str(
+            "\nIP Bans:\n\
+             [#] [IP Address] [Minutes left] [Reason]\n\
+             ----------------------------------------------\n",
+        );
+
+        for (index, ban) in self.bans.iter().enumerate() {
+            if let Target::Ip(ip) = ban.target {
+                let _ = writeln!(text, "{index:<3} {ip:<15} {}", ban.details());
+            }
+        }
+
+        text
+    }
+
+    fn add_ban(&mut self, args: &str) -> String {
+        let mut fields = args.splitn(3, ' ');
+        let Some(target) = fields.next().and_then(|target| target.parse().ok()) else {
+            return INVALID_BAN_FORMAT_MESSAGE.to_string();
+        };
+        let Some(minutes) = fields.next().and_then(|minutes| minutes.parse().ok()) else {
+            return INVALID_BAN_FORMAT_MESSAGE.to_string();
+        };
+
+        self.add(Ban {
+            target,
+            minutes: (minutes != 0).then_some(minutes),
+            reason: fields.next().map(ToString::to_string),
+        });
+        String::new()
+    }
+
+    /// Add an entry to the ban list, keeping GUID bans ahead of IP bans.
+    fn add(&mut self, ban: Ban) {
+        let index = match ban.target {
+            Target::Uuid(_) => self
+                .bans
+                .iter()
+                .position(|ban| matches!(ban.target, Target::Ip(_)))
+                .unwrap_or(self.bans.len()),
+            Target::Ip(_) => self.bans.len(),
+        };
+        self.bans.insert(index, ban);
+    }
+
+    /// Remove the player with the given index and announce the reason.
+    fn kick(&mut self, index: Option<u64>, kind: &str, reason: Option<&str>) -> Option<Player> {
+        let position = self
+            .players
+            .iter()
+            .position(|player| Some(player.index()) == index)?;
+        let player = self.players.remove(position);
+        let reason = reason.map_or_else(|| kind.to_string(), |reason| format!("{kind} ({reason})"));
+        self.notify(format!(
+            "Player #{} {} ({}) has been kicked by BattlEye: {reason}",
+            player.index(),
+            player.name(),
+            player.guid().simple()
+        ));
+        Some(player)
+    }
+
+    fn notify(&self, message: String) {
+        if let Some(server) = &self.server {
+            let _ = server.broadcast(message);
+        }
+    }
+}
+
+/// An entry of the ban list of a [`MockServer`].
+#[derive(Debug)]
+struct Ban {
+    target: Target,
+    minutes: Option<u64>,
+    reason: Option<String>,
+}
+
+impl Ban {
+    /// Format the remaining minutes and the reason.
+    fn details(&self) -> String {
+        let minutes = self
+            .minutes
+            .map_or_else(|| PERM_BAN.to_string(), |minutes| minutes.to_string());
+        self.reason
+            .as_ref()
+            .map_or_else(|| minutes.clone(), |reason| format!("{minutes} {reason}"))
+    }
+}
+
+/// Split the arguments of a command into a player index and an optional reason.
+fn split_index(args: &str) -> (Option<u64>, Option<&str>) {
+    let (index, reason) = args
+        .split_once(' ')
+        .map_or((args, None), |(index, reason)| (index, Some(reason)));
+    (
+        index.parse().ok(),
+        reason.filter(|reason| !reason.is_empty()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use std::time::Duration;
+
+    use uuid::Uuid;
+
+    use super::MockServer;
+    use crate::battleye::{Client, ServerMessage};
+    use crate::dayz::{Event, Player, Target};
+    use crate::{Connect, DayZ};
+
+    const GUID: &str = "0123456789abcdef0123456789abcdef";
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 42));
+
+    fn guid() -> Uuid {
+        GUID.parse().expect("GUID should be valid")
+    }
+
+    fn mock() -> MockServer {
+        MockServer::new()
+            .with_player(Player::new(
+                0,
+                SocketAddr::new(IP, 2304),
+                Duration::from_millis(31),
+                guid(),
+                "John Doe".to_string(),
+            ))
+            .with_player(Player::new(
+                3,
+                SocketAddr::from(([10, 0, 0, 1], 2316)),
+                Duration::from_millis(120),
+                Uuid::nil(),
+                "Jane".to_string(),
+            ))
+            .with_ban(Target::Uuid(guid()), None, Some("Cheating in game"))
+            .with_ban(Target::Ip(IP), Some(Duration::from_hours(1)), None)
+    }
+
+    async fn connect(mock: &MockServer) -> Client {
+        let server = mock.bind("127.0.0.1:0", "secret").await.unwrap();
+        let address = server.local_addr().unwrap();
+        tokio::spawn(server.serve());
+        Client::builder()
+            .connect(address)
+            .await
+            .unwrap()
+            .login("secret")
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_players() {
+        let mock = mock();
+        let mut client = connect(&mock).await;
+        assert_eq!(client.players().await.unwrap(), mock.players());
+    }
+
+    #[tokio::test]
+    async fn test_bans() {
+        let mock = mock();
+        let mut client = connect(&mock).await;
+        assert_eq!(client.bans().await.unwrap(), mock.bans());
+
+        client
+            .add_ban(Target::Ip(IP), None, Some("Spam"))
+            .await
+            .unwrap();
+        client.remove_ban(0).await.unwrap();
+        let bans = client.bans().await.unwrap();
+        assert_eq!(bans, mock.bans());
+        assert_eq!(bans.len(), 2);
+        assert_eq!(bans[1].target(), Target::Ip(IP));
+        assert_eq!(bans[1].duration(), None);
+        assert_eq!(bans[1].reason(), Some("Spam"));
+    }
+
+    #[tokio::test]
+    async fn test_kick_and_ban() {
+        let mock = mock();
+        let mut client = connect(&mock).await;
+        let mut messages = client.subscribe();
+
+        client.kick(3, Some("AFK")).await.unwrap();
+        assert!(matches!(
+            Event::from(&next_message(&mut messages).await),
+            Event::PlayerKicked { index: 3, reason, .. } if reason == "Admin Kick (AFK)"
+        ));
+
+        client.ban(0, None::<&str>).await.unwrap();
+        assert!(matches!(
+            Event::from(&next_message(&mut messages).await),
+            Event::PlayerBanned { index: 0, .. }
+        ));
+        assert!(client.players().await.unwrap().is_empty());
+        assert_eq!(client.bans().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_server_commands() {
+        let mock = mock();
+        let mut client = connect(&mock).await;
+
+        client.lock().await.unwrap();
+        assert!(mock.is_locked());
+        client.unlock().await.unwrap();
+        assert!(!mock.is_locked());
+        client.reload().await.unwrap();
+        assert_eq!(mock.reloads(), 1);
+        client.shutdown().await.unwrap();
+        assert!(mock.is_shut_down());
+    }
+
+    async fn next_message(
+        messages: &mut tokio::sync::broadcast::Receiver<ServerMessage>,
+    ) -> ServerMessage {
+        messages.recv().await.unwrap()
+    }
+}
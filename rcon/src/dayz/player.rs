@@ -6,8 +6,10 @@ use std::time::Duration;
 
 use uuid::Uuid;
 
+use super::fields::{parse_guid, split_field};
+
 /// Information about a player on a `DayZ` server.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Player {
     index: u64,
     socket_addr: SocketAddr,
@@ -17,6 +19,24 @@ pub struct Player {
 }
 
 impl Player {
+    /// Create a new player.
+    #[must_use]
+    pub const fn new(
+        index: u64,
+        socket_addr: SocketAddr,
+        ping: Duration,
+        guid: Uuid,
+        name: String,
+    ) -> Self {
+        Self {
+            index,
+            socket_addr,
+            ping,
+            guid,
+            name,
+        }
+    }
+
     /// The index of the player on the server.
     #[must_use]
     pub const fn index(&self) -> u64 {
@@ -66,29 +86,19 @@ impl FromStr for Player {
     type Err = Cow<'static, str>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut fields = s.split_whitespace();
-        let id = fields
-            .next()
-            .ok_or("missing ID field")?
-            .parse()
-            .map_err(|error| format!("invalid ID: {error}"))?;
-        let socket_addr = fields
-            .next()
-            .ok_or("missing socket address")?
+        let (id, rest) = split_field(s).ok_or("missing ID field")?;
+        let id = id.parse().map_err(|error| format!("invalid ID: {error}"))?;
+        let (socket_addr, rest) = split_field(rest).ok_or("missing socket address")?;
+        let socket_addr = socket_addr
             .parse()
             .map_err(|error| format!("invalid socket address: {error}"))?;
-        let ping: u64 = fields
-            .next()
-            .ok_or("missing ping")?
+        let (ping, rest) = split_field(rest).ok_or("missing ping")?;
+        let ping: u64 = ping
             .parse()
             .map_err(|error| format!("invalid ping: {error}"))?;
-        let guid = fields.next().ok_or("missing GUID")?;
-        let guid = guid
-            .split_once('(')
-            .map_or(guid, |(guid, _)| guid)
-            .parse()
-            .map_err(|error| format!("invalid GUID: {error}"))?;
-        let name = fields.collect::<Vec<_>>().join("");
+        let (guid, name) = split_field(rest).ok_or("missing GUID")?;
+        let guid = parse_guid(guid).map_err(|error| format!("invalid GUID: {error}"))?;
+        let name = name.to_string();
         Ok(Self {
             index: id,
             socket_addr,
@@ -98,3 +108,47 @@ impl FromStr for Player {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::time::Duration;
+
+    use super::Player;
+
+    const GUID: &str = "0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn test_parse_player() {
+        let player: Player =
+            "0   93.184.216.34:2304    47   0123456789abcdef0123456789abcdef(OK) Survivor Bob"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            player,
+            Player::new(
+                0,
+                SocketAddr::from(([93, 184, 216, 34], 2304)),
+                Duration::from_millis(47),
+                GUID.parse().unwrap(),
+                "Survivor Bob".to_string(),
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_unverified_player() {
+        let player: Player =
+            "12  10.0.0.1:2316         120  0123456789abcdef0123456789abcdef(?) Jane  (Lobby)"
+                .parse()
+                .unwrap();
+        assert_eq!(player.index(), 12);
+        assert_eq!(player.name(), "Jane  (Lobby)");
+    }
+
+    #[test]
+    fn test_parse_invalid_player() {
+        assert!("0   93.184.216.34:2304    47".parse::<Player>().is_err());
+        assert!("Players on server:".parse::<Player>().is_err());
+    }
+}
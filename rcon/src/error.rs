@@ -3,7 +3,7 @@
 use std::fmt::Display;
 use std::string::FromUtf8Error;
 
-/// Represents either an I/O error or a UTF-8 error.
+/// Errors that can occur when communicating with an `RCON` server.
 #[derive(Debug)]
 pub enum Error {
     /// An I/O error occurred.
@@ -11,6 +11,62 @@ pub enum Error {
 
     /// A UTF-8 parsing error occurred.
     Utf8(FromUtf8Error),
+
+    /// The server rejected the password.
+    AuthenticationFailed,
+
+    /// The checksum of a received packet does not match its content.
+    ChecksumMismatch,
+
+    /// The ID of a received packet does not match the ID of the request.
+    PacketIdMismatch {
+        /// The ID of the request.
+        expected: i32,
+        /// The ID of the received packet.
+        actual: i32,
+    },
+
+    /// A received packet could not be parsed.
+    MalformedPacket {
+        /// The reason why the packet is malformed.
+        reason: String,
+        /// The offending bytes.
+        bytes: Vec<u8>,
+    },
+
+    /// A received packet exceeds the maximum packet size.
+    ///
+    /// The connection is closed, since the stream cannot be resynchronised.
+    OversizedPacket {
+        /// The size announced by the packet.
+        size: usize,
+        /// The maximum accepted packet size.
+        max_size: usize,
+    },
+
+    /// The server answered a request with a response of a different type,
+    /// such as a command response to a login.
+    UnexpectedResponse,
+
+    /// The server did not respond in time.
+    TimedOut,
+
+    /// The connection to the server has been closed.
+    Disconnected,
+}
+
+#[cfg(any(feature = "battleye", feature = "source"))]
+impl Error {
+    /// Create a new error for a malformed packet.
+    pub(crate) fn malformed_packet<T>(reason: T, bytes: &[u8]) -> Self
+    where
+        T: Display,
+    {
+        Self::MalformedPacket {
+            reason: reason.to_string(),
+            bytes: bytes.to_vec(),
+        }
+    }
 }
 
 impl Display for Error {
@@ -18,6 +74,18 @@ impl Display for Error {
         match self {
             Self::Io(error) => error.fmt(f),
             Self::Utf8(error) => error.fmt(f),
+            Self::AuthenticationFailed => write!(f, "Authentication failed"),
+            Self::ChecksumMismatch => write!(f, "Checksum mismatch"),
+            Self::PacketIdMismatch { expected, actual } => {
+                write!(f, "Packet ID mismatch: {actual} != {expected}")
+            }
+            Self::MalformedPacket { reason, .. } => write!(f, "Malformed packet: {reason}"),
+            Self::OversizedPacket { size, max_size } => {
+                write!(f, "Oversized packet: {size} > {max_size} bytes")
+            }
+            Self::UnexpectedResponse => write!(f, "Unexpected response type"),
+            Self::TimedOut => write!(f, "Timed out waiting for a response"),
+            Self::Disconnected => write!(f, "Disconnected from server"),
         }
     }
 }
@@ -27,6 +95,14 @@ impl std::error::Error for Error {
         match self {
             Self::Io(error) => Some(error),
             Self::Utf8(error) => Some(error),
+            Self::AuthenticationFailed
+            | Self::ChecksumMismatch
+            | Self::PacketIdMismatch { .. }
+            | Self::MalformedPacket { .. }
+            | Self::OversizedPacket { .. }
+            | Self::UnexpectedResponse
+            | Self::TimedOut
+            | Self::Disconnected => None,
         }
     }
 }
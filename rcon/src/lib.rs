@@ -1,59 +1,114 @@
 //! A common interface for different `RCON` protocols.
 
 use std::future::Future;
-use std::net::SocketAddr;
+use std::time::Duration;
+
+#[cfg(any(feature = "battleye", feature = "source"))]
+use tokio::net::ToSocketAddrs;
 
 #[cfg(feature = "dayz")]
 pub use self::dayz::DayZ;
 pub use self::error::Error;
 #[cfg(feature = "minecraft")]
 pub use self::minecraft::Minecraft;
+#[cfg(any(feature = "battleye", feature = "source"))]
+pub use self::retry_policy::RetryPolicy;
 
+#[cfg(any(feature = "battleye", feature = "source"))]
+pub mod any;
 #[cfg(feature = "battleye")]
 pub mod battleye;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 #[cfg(feature = "dayz")]
 pub mod dayz;
 mod error;
 #[cfg(feature = "minecraft")]
 pub mod minecraft;
+#[cfg(any(feature = "battleye", feature = "source"))]
+pub mod pool;
+#[cfg(any(feature = "battleye", feature = "source"))]
+mod resolve;
+#[cfg(any(feature = "battleye", feature = "source"))]
+mod retry_policy;
+#[cfg(all(feature = "server", any(feature = "battleye", feature = "source")))]
+mod server;
 #[cfg(feature = "source")]
 pub mod source;
+#[cfg(any(feature = "battleye", feature = "source"))]
+mod timeout;
 
-/// Common API for `RCON` protocol clients
-pub trait RCon {
-    /// Create an `RCON` client by connecting to the specified address.
+/// Common API for connecting and logging in to `RCON` servers.
+///
+/// Commands can only be sent by the [`RCon`] client returned by a successful login.
+#[cfg(any(feature = "battleye", feature = "source"))]
+pub trait Connect: Sized {
+    /// The authenticated client returned after logging in.
+    type Client: RCon;
+
+    /// Connect to the specified address.
+    ///
+    /// The address may be a host name, such as `"mc.example.org:25575"`.
+    /// Each address it resolves to is tried in turn until a connection is established.
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if any I/O errors occurred.
-    fn connect<T>(address: T) -> impl Future<Output = std::io::Result<Self>> + Send
+    /// Returns an [`Error`] if the address cannot be resolved or no connection could be established.
+    fn connect<T>(address: T) -> impl Future<Output = Result<Self, Error>> + Send
     where
-        Self: Sized,
-        T: Into<SocketAddr> + Send;
+        T: ToSocketAddrs + Send;
 
-    /// Perform a login.
+    /// Perform a login, consuming the connection.
+    ///
+    /// The login is subject to the connection's default timeout.
     ///
     /// # Returns
     ///
-    /// Returns `true` if the login succeeded, otherwise `false`.
+    /// Returns the authenticated client.
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if any I/O errors occurred.
-    fn login<T>(&mut self, password: T) -> impl Future<Output = std::io::Result<bool>> + Send
+    /// Returns [`Error::AuthenticationFailed`] if the server rejected the password.
+    /// Returns another [`Error`] if any I/O or protocol errors occurred.
+    /// If the server does not respond in time, [`Error::TimedOut`] is returned.
+    fn login<T>(self, password: T) -> impl Future<Output = Result<Self::Client, Error>> + Send
     where
         T: AsRef<[u8]> + Send;
+}
 
+/// Common API for authenticated `RCON` protocol clients.
+pub trait RCon {
     /// Run a command.
     ///
+    /// The command is subject to the client's default timeout.
+    ///
+    /// # Returns
+    ///
+    /// Returns the raw bytes from the server's response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if any I/O or protocol errors occurred.
+    /// If the server does not respond in time, [`Error::TimedOut`] is returned.
+    fn run<T>(&mut self, command: T) -> impl Future<Output = Result<Vec<u8>, Error>> + Send
+    where
+        T: AsRef<[u8]> + Send;
+
+    /// Run a command with a timeout overriding the client's default timeout.
+    ///
     /// # Returns
     ///
     /// Returns the raw bytes from the server's response.
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if any I/O errors occurred.
-    fn run<T>(&mut self, command: T) -> impl Future<Output = std::io::Result<Vec<u8>>> + Send
+    /// Returns an [`Error`] if any I/O or protocol errors occurred.
+    /// If the server does not respond within `timeout`, [`Error::TimedOut`] is returned.
+    fn run_with_timeout<T>(
+        &mut self,
+        command: T,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<Vec<u8>, Error>> + Send
     where
         T: AsRef<[u8]> + Send;
 
@@ -65,7 +120,7 @@ pub trait RCon {
     ///
     /// # Errors
     ///
-    /// Returns an [`Error`] if any I/O errors occurred or if the returned bytes are not valid UTF-8.
+    /// Returns an [`Error`] if any I/O or protocol errors occurred or if the returned bytes are not valid UTF-8.
     fn run_utf8<T>(&mut self, command: T) -> impl Future<Output = Result<String, Error>> + Send
     where
         Self: Send,
@@ -87,11 +142,11 @@ pub trait RCon {
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if any I/O errors occurred.
+    /// Returns an [`Error`] if any I/O or protocol errors occurred.
     fn run_utf8_lossy<T>(
         &mut self,
         command: T,
-    ) -> impl Future<Output = std::io::Result<String>> + Send
+    ) -> impl Future<Output = Result<String, Error>> + Send
     where
         Self: Send,
         T: AsRef<[u8]> + Send,
@@ -28,6 +28,8 @@ mod error;
 mod game_mode;
 #[cfg(feature = "minecraft-java-edition")]
 pub mod java_edition;
+#[cfg(feature = "server")]
+pub mod mock;
 mod negate;
 mod proxy;
 mod range;
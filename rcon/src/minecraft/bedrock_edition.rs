@@ -4,7 +4,7 @@ mod camera;
 
 use std::future::Future;
 
-pub use camera::{Camera, Set, Target};
+pub use camera::{Camera, Color, Set, Target, Time};
 
 use crate::Minecraft;
 use crate::minecraft::java_edition::TargetSelector;
@@ -44,6 +44,6 @@ where
     }
 
     fn camera(&mut self, target: Entity<TargetSelector>) -> impl Camera<'_> {
-        Proxy::new(self, vec![target.serialize()])
+        Proxy::new(self, vec!["camera".into(), target.serialize()])
     }
 }
@@ -10,6 +10,8 @@ pub enum Error {
     Io(std::io::Error),
     /// A UTF-8 error occurred.
     Utf8(FromUtf8Error),
+    /// An `RCON` protocol error occurred.
+    Rcon(crate::Error),
     /// The command is incomplete or unknown.
     UnknownOrIncompleteCommand(String),
 }
@@ -19,6 +21,7 @@ impl Display for Error {
         match self {
             Self::Io(error) => error.fmt(f),
             Self::Utf8(error) => error.fmt(f),
+            Self::Rcon(error) => error.fmt(f),
             Self::UnknownOrIncompleteCommand(command) => write!(f, "Unknown command: {command}"),
         }
     }
@@ -29,6 +32,7 @@ impl std::error::Error for Error {
         match self {
             Self::Io(error) => Some(error),
             Self::Utf8(error) => Some(error),
+            Self::Rcon(error) => Some(error),
             Self::UnknownOrIncompleteCommand(_) => None,
         }
     }
@@ -51,6 +55,7 @@ impl From<crate::Error> for Error {
         match error {
             crate::Error::Io(error) => Self::Io(error),
             crate::Error::Utf8(error) => Self::Utf8(error),
+            error => Self::Rcon(error),
         }
     }
 }
@@ -133,7 +133,7 @@ where
     where
         S: AsRef<str> + Send,
     {
-        let mut args = vec![Cow::Borrowed("ban_ip"), target.serialize()];
+        let mut args = vec![Cow::Borrowed("ban-ip"), target.serialize()];
 
         if let Some(reason) = &reason {
             args.push(Cow::Borrowed(reason.as_ref()));
@@ -4,8 +4,8 @@ use std::future::Future;
 
 pub use grant::Grant;
 
+use crate::minecraft::Serialize;
 use crate::minecraft::proxy::Proxy;
-use crate::minecraft::{Serialize, parse_response};
 use crate::{RCon, minecraft};
 
 mod grant;
@@ -38,10 +38,10 @@ where
     T: RCon + Send,
 {
     async fn grant(&mut self, grant: Grant) -> Result<String, minecraft::Error> {
-        parse_response(self.run_utf8(&["grant".into(), grant.serialize()]).await?)
+        self.run_utf8(&["grant".into(), grant.serialize()]).await
     }
 
     async fn revoke(&mut self, grant: Grant) -> Result<String, minecraft::Error> {
-        parse_response(self.run_utf8(&["revoke".into(), grant.serialize()]).await?)
+        self.run_utf8(&["revoke".into(), grant.serialize()]).await
     }
 }
@@ -95,7 +95,7 @@ where
     }
 
     async fn set_base(&mut self, value: f64) -> Result<String, Error> {
-        self.run_utf8(&["base".into(), "get".into(), value.serialize()])
+        self.run_utf8(&["base".into(), "set".into(), value.serialize()])
             .await
     }
 
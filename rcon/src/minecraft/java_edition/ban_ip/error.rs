@@ -8,6 +8,8 @@ pub enum Error {
     Io(std::io::Error),
     /// A UTF-8 error occurred.
     Utf8(FromUtf8Error),
+    /// An `RCON` protocol error occurred.
+    Rcon(crate::Error),
     /// The specified player is unknown.
     UnknownPlayer,
 }
@@ -17,6 +19,7 @@ impl Display for Error {
         match self {
             Self::Io(error) => error.fmt(f),
             Self::Utf8(error) => error.fmt(f),
+            Self::Rcon(error) => error.fmt(f),
             Self::UnknownPlayer => write!(f, "Unknown player"),
         }
     }
@@ -27,6 +30,7 @@ impl std::error::Error for Error {
         match self {
             Self::Io(error) => Some(error),
             Self::Utf8(error) => Some(error),
+            Self::Rcon(error) => Some(error),
             Self::UnknownPlayer => None,
         }
     }
@@ -49,6 +53,7 @@ impl From<crate::Error> for Error {
         match error {
             crate::Error::Io(error) => Self::Io(error),
             crate::Error::Utf8(error) => Self::Utf8(error),
+            error => Self::Rcon(error),
         }
     }
 }
@@ -8,6 +8,8 @@ pub enum Error {
     Io(std::io::Error),
     /// A UTF-8 error occurred.
     Utf8(FromUtf8Error),
+    /// An `RCON` protocol error occurred.
+    Rcon(crate::Error),
     /// An invalid ban list entry was encountered.
     InvalidEntry(String),
 }
@@ -17,6 +19,7 @@ impl Display for Error {
         match self {
             Self::Io(err) => err.fmt(f),
             Self::Utf8(err) => err.fmt(f),
+            Self::Rcon(err) => err.fmt(f),
             Self::InvalidEntry(text) => write!(f, "Invalid entry: {text}"),
         }
     }
@@ -27,6 +30,7 @@ impl std::error::Error for Error {
         match self {
             Self::Io(err) => Some(err),
             Self::Utf8(err) => Some(err),
+            Self::Rcon(err) => Some(err),
             Self::InvalidEntry(_) => None,
         }
     }
@@ -49,6 +53,7 @@ impl From<crate::Error> for Error {
         match error {
             crate::Error::Io(err) => Self::Io(err),
             crate::Error::Utf8(err) => Self::Utf8(err),
+            err => Self::Rcon(err),
         }
     }
 }
@@ -0,0 +1,1597 @@
+//! A mock Minecraft server for testing `Source RCON` clients.
+//!
+//! The mock keeps its players, ban lists, advancements, attributes and bossbars in memory
+//! and answers the commands used by the `JavaEdition` and `BedrockEdition` traits
+//! with the messages of a vanilla server.
+//! Unknown or malformed commands are answered with an "Unknown or incomplete command" error.
+//! Commands without feedback, such as `camera`, are answered with an empty response.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display, Write};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use uuid::Uuid;
+
+use super::util::UNKNOWN_OR_INCOMPLETE_COMMAND;
+use crate::source::server::{Handler, SHORT_PACKETS_SPLIT_SIZE, Server};
+
+/// The name under which commands issued via `RCON` are executed.
+const RCON: &str = "Rcon";
+const DEFAULT_BAN_REASON: &str = "Banned by an operator.";
+const NO_PLAYER_FOUND: &str = "No player was found";
+const NO_ENTITY_FOUND: &str = "No entity was found";
+const NO_TARGETS_MATCHED: &str = "No targets matched selector";
+const ONLY_ONE_ENTITY: &str =
+    "Only one entity is allowed, but the provided selector allows more than one";
+const UNKNOWN_PLAYER: &str = "That player does not exist";
+/// Number of characters of a command shown in front of the position of a syntax error.
+const CONTEXT_SIZE: usize = 10;
+
+/// The advancements known to the mock alongside their single criterion.
+///
+/// Each advancement is the parent of the next one.
+const ADVANCEMENTS: [(&str, &str); 4] = [
+    ("minecraft:story/root", "crafting_table"),
+    ("minecraft:story/mine_stone", "get_stone"),
+    ("minecraft:story/upgrade_tools", "stone_pickaxe"),
+    ("minecraft:story/smelt_iron", "iron"),
+];
+
+/// The attributes of players alongside their default base values.
+const ATTRIBUTES: [(&str, f64); 4] = [
+    ("minecraft:generic.armor", 0.0),
+    ("minecraft:generic.attack_damage", 1.0),
+    ("minecraft:generic.max_health", 20.0),
+    ("minecraft:generic.movement_speed", 0.1),
+];
+
+const BOSSBAR_COLORS: [&str; 7] = ["blue", "green", "pink", "purple", "red", "white", "yellow"];
+const BOSSBAR_STYLES: [&str; 5] = [
+    "notched_6",
+    "notched_10",
+    "notched_12",
+    "notched_20",
+    "progress",
+];
+const CAMERA_PRESETS: [&str; 4] = [
+    "minecraft:first_person",
+    "minecraft:free",
+    "minecraft:third_person",
+    "minecraft:third_person_front",
+];
+
+const USAGE: [&str; 10] = [
+    "/advancement (grant|revoke)",
+    "/alwaysday [<lock>]",
+    "/attribute <target> <attribute> (base|get|modifier)",
+    "/ban <targets> [<reason>]",
+    "/ban-ip <target> [<reason>]",
+    "/banlist [ips|players]",
+    "/bossbar (add|get|list|remove|set)",
+    "/camera <players> (clear|fade|set)",
+    "/daylock [<lock>]",
+    "/help [<command>]",
+];
+
+/// A mock Minecraft server.
+///
+/// Clones share the same state, so that the state can be inspected
+/// while a clone handles the commands of a [`Server`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::net::{IpAddr, Ipv4Addr};
+///
+/// use rcon::minecraft::mock::MockServer;
+/// use tokio::net::TcpListener;
+///
+/// # async fn serve() -> Result<(), rcon::Error> {
+/// let mock = MockServer::new().with_player("Steve", IpAddr::V4(Ipv4Addr::LOCALHOST));
+/// let listener = TcpListener::bind("127.0.0.1:25575").await?;
+/// mock.server("secret").serve(listener).await
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MockServer {
+    state: Arc<Mutex<State>>,
+}
+
+impl MockServer {
+    /// Create a mock server without players.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an online player with the given name and IP address.
+    #[must_use]
+    pub fn with_player(self, name: &str, ip: IpAddr) -> Self {
+        self.state().players.push(Player {
+            name: name.to_string(),
+            ip,
+        });
+        self
+    }
+
+    /// Create a `Source RCON` server serving this mock.
+    ///
+    /// Like a vanilla server, it splits responses at [`SHORT_PACKETS_SPLIT_SIZE`],
+    /// so clients should be configured with [`Quirks::MINECRAFT`](crate::source::Quirks::MINECRAFT).
+    #[must_use]
+    pub fn server<P>(&self, password: P) -> Server<Self>
+    where
+        P: AsRef<[u8]>,
+    {
+        Server::new(password, self.clone()).with_split_size(SHORT_PACKETS_SPLIT_SIZE)
+    }
+
+    /// Returns the names of the online players.
+    #[must_use]
+    pub fn players(&self) -> Vec<String> {
+        self.state()
+            .players
+            .iter()
+            .map(|player| player.name.clone())
+            .collect()
+    }
+
+    /// Returns the names of the banned players.
+    #[must_use]
+    pub fn banned_players(&self) -> Vec<String> {
+        self.state()
+            .bans
+            .iter()
+            .map(|ban| ban.target.clone())
+            .collect()
+    }
+
+    /// Returns the banned IP addresses.
+    #[must_use]
+    pub fn banned_ips(&self) -> Vec<IpAddr> {
+        self.state().ip_bans.iter().map(|ban| ban.target).collect()
+    }
+
+    /// Returns `true` if the day-night cycle is locked.
+    #[must_use]
+    pub fn is_day_locked(&self) -> bool {
+        self.state().day_locked
+    }
+
+    /// Run a command and return the server's response.
+    #[must_use]
+    pub fn run(&self, command: &str) -> String {
+        self.state().run(command)
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Handler for MockServer {
+    async fn handle(&self, command: Vec<u8>) -> Vec<u8> {
+        self.run(&String::from_utf8_lossy(&command)).into_bytes()
+    }
+}
+
+/// The mutable state of a [`MockServer`].
+#[derive(Debug, Default)]
+struct State {
+    players: Vec<Player>,
+    bans: Vec<Ban<String>>,
+    ip_bans: Vec<Ban<IpAddr>>,
+    advancements: BTreeMap<String, BTreeSet<&'static str>>,
+    attributes: BTreeMap<(String, &'static str), Attribute>,
+    bossbars: BTreeMap<String, Bossbar>,
+    day_locked: bool,
+}
+
+impl State {
+    fn run(&mut self, command: &str) -> String {
+        let mut args = Args(command);
+        let result = match args.next() {
+            Some("advancement") => self.advancement(&mut args),
+            Some("alwaysday" | "daylock") => self.day_lock(&mut args),
+            Some("attribute") => self.attribute(&mut args),
+            Some("ban") => self.ban(&mut args),
+            Some("ban-ip") => self.ban_ip(&mut args),
+            Some("banlist") => self.banlist(&mut args),
+            Some("bossbar") => self.bossbar(&mut args),
+            Some("camera") => self.camera(&mut args),
+            Some("help") => Ok(help(&mut args)),
+            _ => Err(SyntaxError::Incomplete),
+        };
+
+        match result {
+            Ok(response) => response,
+            Err(SyntaxError::Incomplete) => {
+                format!("{UNKNOWN_OR_INCOMPLETE_COMMAND}\n{}", here(command))
+            }
+            Err(SyntaxError::Argument(message)) => format!("{message}\n{}", here(command)),
+        }
+    }
+
+    fn advancement(&mut self, args: &mut Args<'_>) -> Result<String, SyntaxError> {
+        let players = self.select(args.expect()?)?;
+        let grant = match args.expect()? {
+            "grant" => true,
+            "revoke" => false,
+            _ => return Err(SyntaxError::Incomplete),
+        };
+        let (advancements, criterion) = match args.expect()? {
+            "everything" => (&ADVANCEMENTS[..], None),
+            "only" => {
+                let index = advancement_index(args.expect()?)?;
+                (&ADVANCEMENTS[index..=index], args.next())
+            }
+            "from" => (&ADVANCEMENTS[advancement_index(args.expect()?)?..], None),
+            "through" => {
+                advancement_index(args.expect()?)?;
+                (&ADVANCEMENTS[..], None)
+            }
+            "until" => (&ADVANCEMENTS[..=advancement_index(args.expect()?)?], None),
+            _ => return Err(SyntaxError::Incomplete),
+        };
+        args.end()?;
+
+        if players.is_empty() {
+            return Ok(NO_PLAYER_FOUND.to_string());
+        }
+
+        if let (Some(criterion), [(id, known)]) = (criterion, advancements)
+            && criterion != *known
+        {
+            return Ok(format!(
+                "The advancement [{id}] does not contain the criterion '{criterion}'"
+            ));
+        }
+
+        let mut changed = false;
+
+        for player in &players {
+            let granted = self.advancements.entry(player.name.clone()).or_default();
+
+            for (id, _) in advancements {
+                changed |= if grant {
+                    granted.insert(id)
+                } else {
+                    granted.remove(id)
+                };
+            }
+        }
+
+        let wording = Wording::new(grant);
+        let players = describe(&players);
+        Ok(match (advancements, criterion) {
+            ([(id, _)], Some(criterion)) => wording.criterion(changed, criterion, id, &players),
+            ([(id, _)], None) => wording.advancement(changed, id, &players),
+            _ => wording.advancements(changed, advancements.len(), &players),
+        })
+    }
+
+    fn attribute(&mut self, args: &mut Args<'_>) -> Result<String, SyntaxError> {
+        let players = self.select(args.expect()?)?;
+        let id = resource_location(args.expect()?)?;
+        let command = match args.expect()? {
+            "get" => {
+                let _: Option<f64> = args.parse_optional()?;
+                AttributeCommand::Get
+            }
+            "base" => match args.expect()? {
+                "get" => {
+                    let _: Option<f64> = args.parse_optional()?;
+                    AttributeCommand::BaseGet
+                }
+                "set" => AttributeCommand::BaseSet(args.parse()?),
+                _ => return Err(SyntaxError::Incomplete),
+            },
+            "modifier" => match args.expect()? {
+                "add" => {
+                    let uuid = args.parse()?;
+                    let _name = args.expect()?;
+                    AttributeCommand::AddModifier(Modifier {
+                        uuid,
+                        amount: args.parse()?,
+                        operation: args.parse()?,
+                    })
+                }
+                "remove" => AttributeCommand::RemoveModifier(args.parse()?),
+                "value" if args.expect()? == "get" => {
+                    let uuid = args.parse()?;
+                    let _: Option<f64> = args.parse_optional()?;
+                    AttributeCommand::ModifierValue(uuid)
+                }
+                _ => return Err(SyntaxError::Incomplete),
+            },
+            _ => return Err(SyntaxError::Incomplete),
+        };
+        args.end()?;
+
+        let [player] = players.as_slice() else {
+            return Ok(if players.is_empty() {
+                NO_ENTITY_FOUND
+            } else {
+                ONLY_ONE_ENTITY
+            }
+            .to_string());
+        };
+        let name = &player.name;
+        let Some(&(id, base)) = ATTRIBUTES.iter().find(|(attribute, _)| *attribute == id) else {
+            return Ok(format!("Entity {name} has no attribute {id}"));
+        };
+        let attribute = self
+            .attributes
+            .entry((name.clone(), id))
+            .or_insert_with(|| Attribute::new(base));
+
+        Ok(match command {
+            AttributeCommand::Get => format!(
+                "Value of attribute {id} for entity {name} is {:?}",
+                attribute.value()
+            ),
+            AttributeCommand::BaseGet => format!(
+                "Base value of attribute {id} for entity {name} is {:?}",
+                attribute.base
+            ),
+            AttributeCommand::BaseSet(value) => {
+                attribute.base = value;
+                format!("Base value for attribute {id} for entity {name} set to {value:?}")
+            }
+            AttributeCommand::AddModifier(modifier) => {
+                let uuid = modifier.uuid;
+
+                if attribute.modifier(uuid).is_some() {
+                    format!(
+                        "Modifier {uuid} is already present on attribute {id} for entity {name}"
+                    )
+                } else {
+                    attribute.modifiers.push(modifier);
+                    format!("Added modifier {uuid} to attribute {id} for entity {name}")
+                }
+            }
+            AttributeCommand::RemoveModifier(uuid) => {
+                if attribute.modifier(uuid).is_some() {
+                    attribute.modifiers.retain(|modifier| modifier.uuid != uuid);
+                    format!("Removed modifier {uuid} from attribute {id} for entity {name}")
+                } else {
+                    format!("Attribute {id} for entity {name} has no modifier {uuid}")
+                }
+            }
+            AttributeCommand::ModifierValue(uuid) => attribute.modifier(uuid).map_or_else(
+                || format!("Attribute {id} for entity {name} has no modifier {uuid}"),
+                |modifier| {
+                    format!(
+                        "Value of modifier {uuid} on attribute {id} for entity {name} is {:?}",
+                        modifier.amount
+                    )
+                },
+            ),
+        })
+    }
+
+    fn ban(&mut self, args: &mut Args<'_>) -> Result<String, SyntaxError> {
+        let target = args.expect()?;
+        let reason = args.rest().unwrap_or(DEFAULT_BAN_REASON).to_string();
+        let names: Vec<String> = if target.starts_with('@') {
+            self.select(target)?
+                .into_iter()
+                .map(|player| player.name)
+                .collect()
+        } else if is_valid_name(target) {
+            // Banned players are still known to the server after they have been kicked.
+            let Some(name) = self
+                .players
+                .iter()
+                .map(|player| &player.name)
+                .chain(self.bans.iter().map(|ban| &ban.target))
+                .find(|name| name.eq_ignore_ascii_case(target))
+            else {
+                return Ok(UNKNOWN_PLAYER.to_string());
+            };
+            vec![name.clone()]
+        } else {
+            return Err(SyntaxError::Incomplete);
+        };
+
+        if names.is_empty() {
+            return Ok(NO_PLAYER_FOUND.to_string());
+        }
+
+        let mut lines = Vec::new();
+
+        for name in names {
+            if self.bans.iter().any(|ban| ban.target == name) {
+                continue;
+            }
+
+            self.players.retain(|player| player.name != name);
+            lines.push(format!("Banned {name}: {reason}"));
+            self.bans.push(Ban {
+                target: name,
+                reason: reason.clone(),
+            });
+        }
+
+        if lines.is_empty() {
+            Ok("Nothing changed. The player is already banned".to_string())
+        } else {
+            Ok(lines.join("\n"))
+        }
+    }
+
+    fn ban_ip(&mut self, args: &mut Args<'_>) -> Result<String, SyntaxError> {
+        let target = args.expect()?;
+        let reason = args.rest().unwrap_or(DEFAULT_BAN_REASON).to_string();
+        let ip = match target.parse() {
+            Ok(ip) => ip,
+            Err(_) => match self
+                .players
+                .iter()
+                .find(|player| player.name.eq_ignore_ascii_case(target))
+            {
+                Some(player) => player.ip,
+                None => return Ok("Invalid IP address or unknown player".to_string()),
+            },
+        };
+
+        if self.ip_bans.iter().any(|ban| ban.target == ip) {
+            return Ok("Nothing changed. That IP is already banned".to_string());
+        }
+
+        let mut response = format!("Banned IP {ip}: {reason}");
+        self.ip_bans.push(Ban { target: ip, reason });
+        let affected: Vec<String> = self
+            .players
+            .iter()
+            .filter(|player| player.ip == ip)
+            .map(|player| player.name.clone())
+            .collect();
+
+        if !affected.is_empty() {
+            self.players.retain(|player| player.ip != ip);
+            let _ = write!(
+                response,
+                "\nThis ban affects {} player(s): {}",
+                affected.len(),
+                affected.join(", ")
+            );
+        }
+
+        Ok(response)
+    }
+
+    fn banlist(&self, args: &mut Args<'_>) -> Result<String, SyntaxError> {
+        let (players, ips) = match args.next() {
+            None => (true, true),
+            Some("players") => (true, false),
+            Some("ips") => (false, true),
+            Some(_) => return Err(SyntaxError::Incomplete),
+        };
+        args.end()?;
+        let mut entries = Vec::new();
+
+        if players {
+            entries.extend(self.bans.iter().map(ToString::to_string));
+        }
+
+        if ips {
+            entries.extend(self.ip_bans.iter().map(ToString::to_string));
+        }
+
+        if entries.is_empty() {
+            return Ok("There are no bans".to_string());
+        }
+
+        Ok(format!(
+            "There are {} ban(s):\n{}",
+            entries.len(),
+            entries.join("\n")
+        ))
+    }
+
+    fn bossbar(&mut self, args: &mut Args<'_>) -> Result<String, SyntaxError> {
+        match args.expect()? {
+            "add" => {
+                let id = resource_location(args.expect()?)?;
+                let bossbar = Bossbar::new(text(args.rest())?);
+
+                if self.bossbars.contains_key(&id) {
+                    return Ok(format!("A bossbar already exists with the ID '{id}'"));
+                }
+
+                let response = format!("Created custom bossbar {bossbar}");
+                self.bossbars.insert(id, bossbar);
+                Ok(response)
+            }
+            "get" => {
+                let id = resource_location(args.expect()?)?;
+                let setting = choice(args.expect()?, &["max", "players", "value", "visible"])?;
+                args.end()?;
+                let Some(bossbar) = self.bossbars.get(&id) else {
+                    return Ok(unknown_bossbar(&id));
+                };
+
+                Ok(match setting {
+                    "max" => format!("Custom bossbar {bossbar} has a maximum of {}", bossbar.max),
+                    "value" => format!("Custom bossbar {bossbar} has a value of {}", bossbar.value),
+                    "visible" if bossbar.visible => {
+                        format!("Custom bossbar {bossbar} is currently shown")
+                    }
+                    "visible" => format!("Custom bossbar {bossbar} is currently hidden"),
+                    _ => {
+                        let online: Vec<&str> = bossbar
+                            .players
+                            .iter()
+                            .filter(|name| self.players.iter().any(|player| player.name == **name))
+                            .map(String::as_str)
+                            .collect();
+
+                        if online.is_empty() {
+                            format!("Custom bossbar {bossbar} has no players currently online")
+                        } else {
+                            format!(
+                                "Custom bossbar {bossbar} has {} player(s) currently online: {}",
+                                online.len(),
+                                online.join(", ")
+                            )
+                        }
+                    }
+                })
+            }
+            "list" => {
+                args.end()?;
+
+                if self.bossbars.is_empty() {
+                    return Ok("There are no custom bossbars active".to_string());
+                }
+
+                Ok(format!(
+                    "There are {} custom bossbar(s) active: {}",
+                    self.bossbars.len(),
+                    self.bossbars
+                        .values()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            }
+            "remove" => {
+                let id = resource_location(args.expect()?)?;
+                args.end()?;
+                Ok(self.bossbars.remove(&id).map_or_else(
+                    || unknown_bossbar(&id),
+                    |bossbar| format!("Removed custom bossbar {bossbar}"),
+                ))
+            }
+            "set" => {
+                let id = resource_location(args.expect()?)?;
+                let setting = self.bossbar_setting(args)?;
+                let Some(bossbar) = self.bossbars.get_mut(&id) else {
+                    return Ok(unknown_bossbar(&id));
+                };
+                Ok(bossbar.set(setting))
+            }
+            _ => Err(SyntaxError::Incomplete),
+        }
+    }
+
+    fn bossbar_setting(&self, args: &mut Args<'_>) -> Result<Setting, SyntaxError> {
+        let setting = match args.expect()? {
+            "color" => Setting::Color(choice(args.expect()?, &BOSSBAR_COLORS)?),
+            "max" => Setting::Max(args.parse().and_then(|max| at_least(max, 1))?),
+            "name" => return text(args.rest()).map(Setting::Name),
+            "players" => Setting::Players(
+                args.next()
+                    .map(|target| self.select(target))
+                    .transpose()?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|player| player.name)
+                    .collect(),
+            ),
+            "style" => Setting::Style(choice(args.expect()?, &BOSSBAR_STYLES)?),
+            "value" => Setting::Value(args.parse().and_then(|value| at_least(value, 0))?),
+            "visible" => Setting::Visible(args.parse()?),
+            _ => return Err(SyntaxError::Incomplete),
+        };
+        args.end()?;
+        Ok(setting)
+    }
+
+    fn camera(&self, args: &mut Args<'_>) -> Result<String, SyntaxError> {
+        let players = self.select(args.expect()?)?;
+
+        match args.expect()? {
+            "clear" => (),
+            "fade" => {
+                let mut next = args.next();
+
+                if next == Some("time") {
+                    for _ in 0..3 {
+                        let _: f32 = args.parse()?;
+                    }
+
+                    next = args.next();
+                }
+
+                if next == Some("color") {
+                    for _ in 0..3 {
+                        let _: u8 = args.parse()?;
+                    }
+
+                    next = args.next();
+                }
+
+                if next.is_some() {
+                    return Err(SyntaxError::Incomplete);
+                }
+            }
+            "set" => {
+                choice(&resource_location(args.expect()?)?, &CAMERA_PRESETS)?;
+
+                match args.next() {
+                    None => (),
+                    Some("default") => {
+                        args.rest();
+                    }
+                    Some("facing") => {
+                        let target = args.expect()?;
+
+                        if target.parse::<f32>().is_ok() {
+                            let _: f32 = args.parse()?;
+                            let _: f32 = args.parse()?;
+                        } else {
+                            self.select(target)?;
+                        }
+                    }
+                    Some(_) => return Err(SyntaxError::Incomplete),
+                }
+            }
+            _ => return Err(SyntaxError::Incomplete),
+        }
+
+        args.end()?;
+
+        if players.is_empty() {
+            return Ok(NO_TARGETS_MATCHED.to_string());
+        }
+
+        Ok(String::new())
+    }
+
+    fn day_lock(&mut self, args: &mut Args<'_>) -> Result<String, SyntaxError> {
+        let lock = args.parse_optional()?.unwrap_or(true);
+        args.end()?;
+        self.day_locked = lock;
+        Ok(if lock {
+            "Day-Night cycle locked"
+        } else {
+            "Day-Night cycle unlocked"
+        }
+        .to_string())
+    }
+
+    /// Resolve an entity argument to the matching online players.
+    fn select(&self, target: &str) -> Result<Vec<Player>, SyntaxError> {
+        if let Some(selector) = target.strip_prefix('@') {
+            let kind = selector.split_once('[').map_or(selector, |(kind, _)| kind);
+            return match kind {
+                "a" | "e" => Ok(self.players.clone()),
+                "p" | "r" => Ok(self.players.iter().take(1).cloned().collect()),
+                "s" => Ok(Vec::new()),
+                _ => Err(SyntaxError::Argument(format!(
+                    "Unknown selector type '@{kind}'"
+                ))),
+            };
+        }
+
+        if !is_valid_name(target) {
+            return Err(SyntaxError::Incomplete);
+        }
+
+        Ok(self
+            .players
+            .iter()
+            .filter(|player| player.name.eq_ignore_ascii_case(target))
+            .cloned()
+            .collect())
+    }
+}
+
+/// An online player of a [`MockServer`].
+#[derive(Clone, Debug)]
+struct Player {
+    name: String,
+    ip: IpAddr,
+}
+
+/// An entry of a ban list of a [`MockServer`].
+#[derive(Debug)]
+struct Ban<T> {
+    target: T,
+    reason: String,
+}
+
+impl<T> Display for Ban<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} was banned by {RCON}: {}", self.target, self.reason)
+    }
+}
+
+/// An attribute of a player.
+#[derive(Debug)]
+struct Attribute {
+    base: f64,
+    modifiers: Vec<Modifier>,
+}
+
+impl Attribute {
+    const fn new(base: f64) -> Self {
+        Self {
+            base,
+            modifiers: Vec::new(),
+        }
+    }
+
+    fn modifier(&self, uuid: Uuid) -> Option<&Modifier> {
+        self.modifiers.iter().find(|modifier| modifier.uuid == uuid)
+    }
+
+    /// Calculate the value of the attribute with all modifiers applied.
+    fn value(&self) -> f64 {
+        let amounts = |operation| {
+            self.modifiers
+                .iter()
+                .filter(move |modifier| modifier.operation == operation)
+                .map(|modifier| modifier.amount)
+        };
+        let base = self.base + amounts(Operation::Add).sum::<f64>();
+        let value = base.mul_add(amounts(Operation::MultiplyBase).sum(), base);
+        amounts(Operation::Multiply).fold(value, |value, amount| value * (1.0 + amount))
+    }
+}
+
+/// A sub-command of the `attribute` command.
+enum AttributeCommand {
+    Get,
+    BaseGet,
+    BaseSet(f64),
+    AddModifier(Modifier),
+    RemoveModifier(Uuid),
+    ModifierValue(Uuid),
+}
+
+/// An attribute modifier.
+#[derive(Debug)]
+struct Modifier {
+    uuid: Uuid,
+    amount: f64,
+    operation: Operation,
+}
+
+/// The operation of an attribute modifier.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Operation {
+    Add,
+    Multiply,
+    MultiplyBase,
+}
+
+impl FromStr for Operation {
+    type Err = SyntaxError;
+
+    fn from_str(operation: &str) -> Result<Self, Self::Err> {
+        match operation {
+            "add" => Ok(Self::Add),
+            "multiply" => Ok(Self::Multiply),
+            "multiply_base" => Ok(Self::MultiplyBase),
+            _ => Err(SyntaxError::Incomplete),
+        }
+    }
+}
+
+/// A custom bossbar.
+#[derive(Debug)]
+struct Bossbar {
+    name: String,
+    color: &'static str,
+    max: i32,
+    value: i32,
+    visible: bool,
+    players: Vec<String>,
+    style: &'static str,
+}
+
+impl Bossbar {
+    const fn new(name: String) -> Self {
+        Self {
+            name,
+            color: "white",
+            max: 100,
+            value: 0,
+            visible: true,
+            players: Vec::new(),
+            style: "progress",
+        }
+    }
+
+    /// Apply a setting and describe the change.
+    fn set(&mut self, setting: Setting) -> String {
+        match setting {
+            Setting::Color(color) if color == self.color => {
+                "Nothing changed. That's already the color of this bossbar".to_string()
+            }
+            Setting::Color(color) => {
+                self.color = color;
+                format!("Custom bossbar {self} has changed color")
+            }
+            Setting::Max(max) if max == self.max => {
+                "Nothing changed. That's already the max of this bossbar".to_string()
+            }
+            Setting::Max(max) => {
+                self.max = max;
+                format!("Custom bossbar {self} has changed maximum to {max}")
+            }
+            Setting::Name(name) if name == self.name => {
+                "Nothing changed. That's already the name of this bossbar".to_string()
+            }
+            Setting::Name(name) => {
+                self.name = name;
+                format!("Custom bossbar {self} has been renamed")
+            }
+            Setting::Players(players) if players == self.players => {
+                "Nothing changed. Those players are already on the bossbar with nobody to add or remove"
+                    .to_string()
+            }
+            Setting::Players(players) => {
+                self.players = players;
+
+                if self.players.is_empty() {
+                    format!("Custom bossbar {self} no longer has any players")
+                } else {
+                    format!(
+                        "Custom bossbar {self} now has {} player(s): {}",
+                        self.players.len(),
+                        self.players.join(", ")
+                    )
+                }
+            }
+            Setting::Style(style) if style == self.style => {
+                "Nothing changed. That's already the style of this bossbar".to_string()
+            }
+            Setting::Style(style) => {
+                self.style = style;
+                format!("Custom bossbar {self} has changed style")
+            }
+            Setting::Value(value) if value == self.value => {
+                "Nothing changed. That's already the value of this bossbar".to_string()
+            }
+            Setting::Value(value) => {
+                self.value = value;
+                format!("Custom bossbar {self} has changed value to {value}")
+            }
+            Setting::Visible(true) if self.visible => {
+                "Nothing changed. The bossbar is already visible".to_string()
+            }
+            Setting::Visible(false) if !self.visible => {
+                "Nothing changed. The bossbar is already hidden".to_string()
+            }
+            Setting::Visible(visible) => {
+                self.visible = visible;
+
+                if visible {
+                    format!("Custom bossbar {self} is now visible")
+                } else {
+                    format!("Custom bossbar {self} is now hidden")
+                }
+            }
+        }
+    }
+}
+
+impl Display for Bossbar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}]", self.name)
+    }
+}
+
+/// A setting of a bossbar.
+enum Setting {
+    Color(&'static str),
+    Max(i32),
+    Name(String),
+    Players(Vec<String>),
+    Style(&'static str),
+    Value(i32),
+    Visible(bool),
+}
+
+/// The wording of the responses to granting or revoking advancements.
+struct Wording {
+    done: &'static str,
+    verb: &'static str,
+    preposition: &'static str,
+    condition: &'static str,
+}
+
+impl Wording {
+    const fn new(grant: bool) -> Self {
+        if grant {
+            Self {
+                done: "Granted",
+                verb: "grant",
+                preposition: "to",
+                condition: "already have",
+            }
+        } else {
+            Self {
+                done: "Revoked",
+                verb: "revoke",
+                preposition: "from",
+                condition: "don't have",
+            }
+        }
+    }
+
+    fn advancement(&self, success: bool, id: &str, players: &str) -> String {
+        let Self {
+            done,
+            verb,
+            preposition,
+            condition,
+        } = self;
+
+        if success {
+            format!("{done} the advancement [{id}] {preposition} {players}")
+        } else {
+            format!(
+                "Couldn't {verb} advancement [{id}] {preposition} {players} as they {condition} it"
+            )
+        }
+    }
+
+    fn advancements(&self, success: bool, count: usize, players: &str) -> String {
+        let Self {
+            done,
+            verb,
+            preposition,
+            condition,
+        } = self;
+
+        if success {
+            format!("{done} {count} advancements {preposition} {players}")
+        } else {
+            format!(
+                "Couldn't {verb} {count} advancements {preposition} {players} as they {condition} them"
+            )
+        }
+    }
+
+    fn criterion(&self, success: bool, criterion: &str, id: &str, players: &str) -> String {
+        let Self {
+            done,
+            verb,
+            preposition,
+            condition,
+        } = self;
+
+        if success {
+            format!("{done} criterion '{criterion}' of advancement [{id}] {preposition} {players}")
+        } else {
+            format!(
+                "Couldn't {verb} criterion '{criterion}' of advancement [{id}] {preposition} {players} as they {condition} it"
+            )
+        }
+    }
+}
+
+/// An error in the syntax of a command.
+enum SyntaxError {
+    /// The command is unknown or its arguments are incomplete or malformed.
+    Incomplete,
+    /// An argument is invalid.
+    Argument(String),
+}
+
+/// The whitespace-separated arguments of a command.
+struct Args<'command>(&'command str);
+
+impl<'command> Args<'command> {
+    fn next(&mut self) -> Option<&'command str> {
+        let text = self.0.trim_start();
+
+        if text.is_empty() {
+            return None;
+        }
+
+        let (arg, rest) = text.split_once(' ').unwrap_or((text, ""));
+        self.0 = rest;
+        Some(arg)
+    }
+
+    fn expect(&mut self) -> Result<&'command str, SyntaxError> {
+        self.next().ok_or(SyntaxError::Incomplete)
+    }
+
+    fn parse<T>(&mut self) -> Result<T, SyntaxError>
+    where
+        T: FromStr,
+    {
+        self.expect()?.parse().map_err(|_| SyntaxError::Incomplete)
+    }
+
+    fn parse_optional<T>(&mut self) -> Result<Option<T>, SyntaxError>
+    where
+        T: FromStr,
+    {
+        self.next()
+            .map(|arg| arg.parse().map_err(|_| SyntaxError::Incomplete))
+            .transpose()
+    }
+
+    /// Consume the remaining text as a single argument.
+    fn rest(&mut self) -> Option<&'command str> {
+        let rest = self.0.trim();
+        self.0 = "";
+        (!rest.is_empty()).then_some(rest)
+    }
+
+    fn end(&self) -> Result<(), SyntaxError> {
+        if self.0.trim().is_empty() {
+            Ok(())
+        } else {
+            Err(SyntaxError::Incomplete)
+        }
+    }
+}
+
+fn help(args: &mut Args<'_>) -> String {
+    let Some(command) = args.rest() else {
+        return USAGE.join("\n");
+    };
+
+    USAGE
+        .iter()
+        .find(|usage| {
+            usage
+                .strip_prefix('/')
+                .and_then(|usage| usage.split(' ').next())
+                == Some(command)
+        })
+        .map_or_else(
+            || "Unknown command or insufficient permissions".to_string(),
+            ToString::to_string,
+        )
+}
+
+fn advancement_index(id: &str) -> Result<usize, SyntaxError> {
+    let id = resource_location(id)?;
+    ADVANCEMENTS
+        .iter()
+        .position(|(advancement, _)| *advancement == id)
+        .ok_or_else(|| SyntaxError::Argument(format!("Unknown advancement: {id}")))
+}
+
+/// Validate a resource location and add the default namespace if it is missing.
+fn resource_location(id: &str) -> Result<String, SyntaxError> {
+    if id.is_empty()
+        || !id.chars().all(|char| {
+            char.is_ascii_lowercase() || char.is_ascii_digit() || "_-./:".contains(char)
+        })
+    {
+        return Err(SyntaxError::Incomplete);
+    }
+
+    Ok(if id.contains(':') {
+        id.to_string()
+    } else {
+        format!("minecraft:{id}")
+    })
+}
+
+/// Parse a text component, unwrapping plain JSON strings.
+fn text(text: Option<&str>) -> Result<String, SyntaxError> {
+    let text = text.ok_or(SyntaxError::Incomplete)?;
+    Ok(text
+        .strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .unwrap_or(text)
+        .to_string())
+}
+
+fn choice(arg: &str, choices: &[&'static str]) -> Result<&'static str, SyntaxError> {
+    choices
+        .iter()
+        .find(|choice| **choice == arg)
+        .copied()
+        .ok_or(SyntaxError::Incomplete)
+}
+
+const fn at_least(value: i32, min: i32) -> Result<i32, SyntaxError> {
+    if value < min {
+        Err(SyntaxError::Incomplete)
+    } else {
+        Ok(value)
+    }
+}
+
+fn unknown_bossbar(id: &str) -> String {
+    format!("No bossbar exists with the ID '{id}'")
+}
+
+/// Describe the players affected by a command.
+fn describe(players: &[Player]) -> String {
+    if let [player] = players {
+        player.name.clone()
+    } else {
+        format!("{} players", players.len())
+    }
+}
+
+/// Check whether the text is a valid player name.
+fn is_valid_name(name: &str) -> bool {
+    (1..=16).contains(&name.len())
+        && name
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '_')
+}
+
+/// Show the end of the command in front of the position of a syntax error, as the server does.
+fn here(command: &str) -> String {
+    let chars: Vec<char> = command.chars().collect();
+    let context: String = chars[chars.len().saturating_sub(CONTEXT_SIZE)..]
+        .iter()
+        .collect();
+
+    if chars.len() > CONTEXT_SIZE {
+        format!("...{context}<--[HERE]")
+    } else {
+        format!("{context}<--[HERE]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use tokio::net::TcpListener;
+
+    use super::MockServer;
+    use crate::minecraft::util::UNKNOWN_OR_INCOMPLETE_COMMAND;
+    use crate::source::{Client, Quirks};
+
+    const STEVE: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 0, 42));
+    const ALEX: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+    fn mock() -> MockServer {
+        MockServer::new()
+            .with_player("Steve", STEVE)
+            .with_player("Alex", ALEX)
+    }
+
+    async fn connect(mock: &MockServer) -> Client {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(mock.server("secret").serve(listener));
+        Client::builder()
+            .quirk(Quirks::MINECRAFT)
+            .connect(address)
+            .await
+            .unwrap()
+            .login("secret")
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        let mock = mock();
+        assert_eq!(
+            mock.run("foo"),
+            format!("{UNKNOWN_OR_INCOMPLETE_COMMAND}\nfoo<--[HERE]")
+        );
+        assert_eq!(
+            mock.run("bossbar set minecraft:boss"),
+            format!("{UNKNOWN_OR_INCOMPLETE_COMMAND}\n...craft:boss<--[HERE]")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_help() {
+        use crate::Minecraft;
+
+        let mut client = connect(&mock()).await;
+        assert!(
+            client
+                .help(None::<&str>)
+                .await
+                .unwrap()
+                .lines()
+                .any(|line| line == "/ban-ip <target> [<reason>]")
+        );
+        assert_eq!(
+            client.help(Some("banlist")).await.unwrap(),
+            "/banlist [ips|players]"
+        );
+    }
+
+    #[cfg(feature = "minecraft-java-edition")]
+    mod java_edition {
+        use std::num::NonZero;
+
+        use uuid::Uuid;
+
+        use super::{ALEX, STEVE, connect, mock};
+        use crate::minecraft::java_edition::attribute::Modifier;
+        use crate::minecraft::java_edition::ban_ip::Target;
+        use crate::minecraft::java_edition::banlist::EntryType;
+        use crate::minecraft::java_edition::bossbar::{Color, GetTarget, SetTarget};
+        use crate::minecraft::java_edition::{
+            Advancement, Attribute, Bossbar, Grant, TargetSelector, ban, ban_ip,
+        };
+        use crate::minecraft::{Entity, Error, JavaEdition};
+
+        #[tokio::test]
+        async fn test_ban() {
+            let mock = mock();
+            let mut client = connect(&mock).await;
+
+            let entry = client
+                .ban("Steve".into(), Some("Griefing"))
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(entry.target(), &Entity::from("Steve"));
+            assert_eq!(entry.reason(), "Griefing");
+            assert!(client.ban("Steve".into(), None).await.unwrap().is_none());
+            assert!(matches!(
+                client.ban("@x".into(), None).await,
+                Err(ban::Error::UnknownSelector)
+            ));
+            assert!(matches!(
+                client.ban("Herobrine".into(), None).await,
+                Err(ban::Error::Other(_))
+            ));
+
+            let entry = client
+                .ban(TargetSelector::EveryPlayer(None).into(), None)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(entry.target(), &Entity::from("Alex"));
+            assert_eq!(entry.reason(), "Banned by an operator.");
+            assert!(mock.players().is_empty());
+            assert_eq!(mock.banned_players(), ["Steve", "Alex"]);
+        }
+
+        #[tokio::test]
+        async fn test_ban_ip() {
+            let mock = mock();
+            let mut client = connect(&mock).await;
+
+            client.ban_ip(Target::Ip(ALEX), Some("Spam")).await.unwrap();
+            client
+                .ban_ip(Target::Entity("Steve".into()), None::<&str>)
+                .await
+                .unwrap();
+            client.ban_ip(Target::Ip(ALEX), None::<&str>).await.unwrap();
+            assert!(matches!(
+                client
+                    .ban_ip(Target::Entity("Herobrine".into()), None::<&str>)
+                    .await,
+                Err(ban_ip::Error::UnknownPlayer)
+            ));
+            assert!(mock.players().is_empty());
+            assert_eq!(mock.banned_ips(), [ALEX, STEVE]);
+        }
+
+        #[tokio::test]
+        async fn test_banlist() {
+            let mut client = connect(&mock()).await;
+            assert!(client.banlist(None).await.unwrap().is_empty());
+
+            client.ban("Steve".into(), Some("Griefing")).await.unwrap();
+            client.ban_ip(Target::Ip(ALEX), Some("Spam")).await.unwrap();
+
+            let entries = client.banlist(None).await.unwrap();
+            assert_eq!(entries.len(), 2);
+            assert_eq!(entries[0].target(), &Target::Entity("Steve".into()));
+            assert_eq!(entries[0].moderator(), "Rcon");
+            assert_eq!(entries[0].reason(), "Griefing");
+            assert_eq!(entries[1].target(), &Target::Ip(ALEX));
+            assert_eq!(entries[1].reason(), "Spam");
+
+            let entries = client.banlist(Some(EntryType::Ips)).await.unwrap();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].target(), &Target::Ip(ALEX));
+            assert_eq!(
+                client
+                    .banlist(Some(EntryType::Players))
+                    .await
+                    .unwrap()
+                    .len(),
+                1
+            );
+        }
+
+        #[tokio::test]
+        async fn test_advancement() {
+            let mut client = connect(&mock()).await;
+            let mut advancement = client.advancement("Steve".into());
+
+            assert_eq!(
+                advancement.grant(Grant::Everything).await.unwrap(),
+                "Granted 4 advancements to Steve"
+            );
+            assert_eq!(
+                advancement
+                    .grant(Grant::Until("story/smelt_iron".into()))
+                    .await
+                    .unwrap(),
+                "Couldn't grant 4 advancements to Steve as they already have them"
+            );
+            assert_eq!(
+                advancement
+                    .revoke(Grant::Only {
+                        advancement: "minecraft:story/mine_stone".into(),
+                        criterion: Some("get_stone".into()),
+                    })
+                    .await
+                    .unwrap(),
+                "Revoked criterion 'get_stone' of advancement [minecraft:story/mine_stone] from Steve"
+            );
+            assert_eq!(
+                advancement
+                    .revoke(Grant::From("story/upgrade_tools".into()))
+                    .await
+                    .unwrap(),
+                "Revoked 2 advancements from Steve"
+            );
+            assert_eq!(
+                advancement
+                    .grant(Grant::Only {
+                        advancement: "story/root".into(),
+                        criterion: None,
+                    })
+                    .await
+                    .unwrap(),
+                "Couldn't grant advancement [minecraft:story/root] to Steve as they already have it"
+            );
+            assert_eq!(
+                advancement
+                    .revoke(Grant::Through("story/root".into()))
+                    .await
+                    .unwrap(),
+                "Revoked 4 advancements from Steve"
+            );
+            assert!(
+                advancement
+                    .grant(Grant::Everything)
+                    .await
+                    .unwrap()
+                    .starts_with("Granted")
+            );
+
+            drop(advancement);
+            let mut advancement = client.advancement("@x".into());
+            assert!(
+                advancement
+                    .grant(Grant::Everything)
+                    .await
+                    .unwrap()
+                    .starts_with("Unknown selector type '@x'")
+            );
+        }
+
+        #[tokio::test]
+        async fn test_attribute() {
+            let mut client = connect(&mock()).await;
+            let uuid = Uuid::from_u128(0x0123_4567_89ab_cdef);
+            let mut attribute = client.attribute("Steve".into(), "generic.max_health".into());
+
+            assert_eq!(
+                attribute.base(Some(1.0)).await.unwrap(),
+                "Base value of attribute minecraft:generic.max_health for entity Steve is 20.0"
+            );
+            assert_eq!(
+                attribute.set_base(10.0).await.unwrap(),
+                "Base value for attribute minecraft:generic.max_health for entity Steve set to 10.0"
+            );
+            assert_eq!(
+                attribute
+                    .add_modifier(uuid, "boost", 5.0, Modifier::Add)
+                    .await
+                    .unwrap(),
+                format!(
+                    "Added modifier {uuid} to attribute minecraft:generic.max_health for entity Steve"
+                )
+            );
+            assert_eq!(
+                attribute.get(None).await.unwrap(),
+                "Value of attribute minecraft:generic.max_health for entity Steve is 15.0"
+            );
+            assert_eq!(
+                attribute.modifier_value(uuid, Some(2.0)).await.unwrap(),
+                format!(
+                    "Value of modifier {uuid} on attribute minecraft:generic.max_health for entity Steve is 5.0"
+                )
+            );
+            assert_eq!(
+                attribute.remove_modifier(uuid).await.unwrap(),
+                format!(
+                    "Removed modifier {uuid} from attribute minecraft:generic.max_health for entity Steve"
+                )
+            );
+            assert_eq!(
+                attribute.remove_modifier(uuid).await.unwrap(),
+                format!(
+                    "Attribute minecraft:generic.max_health for entity Steve has no modifier {uuid}"
+                )
+            );
+
+            drop(attribute);
+            let mut attribute = client.attribute(
+                TargetSelector::EveryPlayer(None).into(),
+                "generic.max_health".into(),
+            );
+            assert_eq!(
+                attribute.get(None).await.unwrap(),
+                "Only one entity is allowed, but the provided selector allows more than one"
+            );
+        }
+
+        #[tokio::test]
+        async fn test_bossbar() {
+            let mut client = connect(&mock()).await;
+            let mut bossbar = client.bossbar();
+
+            assert_eq!(
+                bossbar.list().await.unwrap(),
+                "There are no custom bossbars active"
+            );
+            assert_eq!(
+                bossbar
+                    .add("boss".into(), "\"Boss\"".to_string())
+                    .await
+                    .unwrap(),
+                "Created custom bossbar [Boss]"
+            );
+            assert_eq!(
+                bossbar
+                    .add("minecraft:boss".into(), "Boss".to_string())
+                    .await
+                    .unwrap(),
+                "A bossbar already exists with the ID 'minecraft:boss'"
+            );
+            assert_eq!(
+                bossbar.list().await.unwrap(),
+                "There are 1 custom bossbar(s) active: [Boss]"
+            );
+            assert_eq!(
+                bossbar
+                    .set("boss".into(), SetTarget::Max(NonZero::new(200).unwrap()))
+                    .await
+                    .unwrap(),
+                "Custom bossbar [Boss] has changed maximum to 200"
+            );
+            assert_eq!(
+                bossbar.get("boss".into(), GetTarget::Max).await.unwrap(),
+                "Custom bossbar [Boss] has a maximum of 200"
+            );
+            assert_eq!(
+                bossbar
+                    .set("boss".into(), SetTarget::Color(Color::White))
+                    .await
+                    .unwrap(),
+                "Nothing changed. That's already the color of this bossbar"
+            );
+            assert_eq!(
+                bossbar
+                    .set("boss".into(), SetTarget::Visible(false))
+                    .await
+                    .unwrap(),
+                "Custom bossbar [Boss] is now hidden"
+            );
+            assert_eq!(
+                bossbar
+                    .get("boss".into(), GetTarget::Visible)
+                    .await
+                    .unwrap(),
+                "Custom bossbar [Boss] is currently hidden"
+            );
+            assert_eq!(
+                bossbar
+                    .get("boss".into(), GetTarget::Players)
+                    .await
+                    .unwrap(),
+                "Custom bossbar [Boss] has no players currently online"
+            );
+            assert_eq!(
+                bossbar.remove("boss".into()).await.unwrap(),
+                "Removed custom bossbar [Boss]"
+            );
+            assert_eq!(
+                bossbar.get("boss".into(), GetTarget::Value).await.unwrap(),
+                "No bossbar exists with the ID 'minecraft:boss'"
+            );
+            assert!(matches!(
+                bossbar.add("boss".into(), String::new()).await,
+                Err(Error::UnknownOrIncompleteCommand(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "minecraft-bedrock-edition")]
+    mod bedrock_edition {
+        use std::time::Duration;
+
+        use super::{connect, mock};
+        use crate::minecraft::BedrockEdition;
+        use crate::minecraft::bedrock_edition::{Camera, Color, Set, Target, Time};
+
+        #[tokio::test]
+        async fn test_day_lock() {
+            let mock = mock();
+            let mut client = connect(&mock).await;
+
+            assert_eq!(
+                client.day_lock(true).await.unwrap(),
+                "Day-Night cycle locked"
+            );
+            assert!(mock.is_day_locked());
+            assert_eq!(
+                client.always_day(false).await.unwrap(),
+                "Day-Night cycle unlocked"
+            );
+            assert!(!mock.is_day_locked());
+        }
+
+        #[tokio::test]
+        async fn test_camera() {
+            let mut client = connect(&mock()).await;
+            let time = Time::new(
+                Duration::from_secs(1),
+                Duration::from_millis(500),
+                Duration::from_secs(1),
+            );
+
+            assert_eq!(client.camera("Steve".into()).clear().await.unwrap(), "");
+            assert_eq!(
+                client
+                    .camera("Steve".into())
+                    .fade(Color::new(255, 0, 0), Some(time))
+                    .await
+                    .unwrap(),
+                ""
+            );
+            assert_eq!(
+                client
+                    .camera("Steve".into())
+                    .set("minecraft:free".into())
+                    .default(None)
+                    .await
+                    .unwrap(),
+                ""
+            );
+            assert_eq!(
+                client
+                    .camera("Steve".into())
+                    .set("minecraft:free".into())
+                    .facing(Target::Position(0.0, 64.0, 0.0))
+                    .await
+                    .unwrap(),
+                ""
+            );
+            assert_eq!(
+                client.camera("Herobrine".into()).clear().await.unwrap(),
+                "No targets matched selector"
+            );
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
 use crate::RCon;
-use crate::minecraft::Error;
+use crate::minecraft::{Error, parse_response};
 
 /// A proxy object to handle sub-commands.
 #[derive(Debug)]
@@ -31,10 +31,15 @@ where
     T: RCon + Send,
 {
     /// Run a command on the underlying `RCON` client.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownOrIncompleteCommand`] if the server did not understand the command.
     pub(crate) async fn run_utf8(&mut self, args: &[Cow<'_, str>]) -> Result<String, Error> {
         self.client
             .run_utf8([&self.args, args].concat().join(" "))
             .await
             .map_err(Into::into)
+            .and_then(parse_response)
     }
 }
@@ -6,7 +6,8 @@ use crate::minecraft::Error;
 
 mod string;
 
-const UNKNOWN_OR_INCOMPLETE_COMMAND: &str = "Unknown or incomplete command, see below for error";
+pub const UNKNOWN_OR_INCOMPLETE_COMMAND: &str =
+    "Unknown or incomplete command, see below for error";
 
 pub fn parse_response(response: String) -> Result<String, Error> {
     if response.starts_with(UNKNOWN_OR_INCOMPLETE_COMMAND) {
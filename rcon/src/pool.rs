@@ -0,0 +1,369 @@
+//! A pool of authenticated `RCON` clients.
+//!
+//! Connecting and logging in for every command is slow and may trip the server's rate limits.
+//! The [`Pool`] keeps up to a given amount of authenticated clients per server address
+//! and hands them out as [`Guard`]s, which return the client to the pool when dropped.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::num::NonZero;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use log::{debug, warn};
+use tokio::sync::Semaphore;
+
+pub use self::builder::PoolBuilder;
+pub use self::guard::Guard;
+use crate::{Connect, Error, RCon};
+
+mod builder;
+mod guard;
+
+/// A pool of authenticated `RCON` clients, keyed by server address.
+///
+/// Idle clients are only handed out to callers presenting the password they logged in with.
+/// The pool can be cloned cheaply. All clones share the same clients.
+#[derive(Debug)]
+pub struct Pool<C>
+where
+    C: Connect,
+{
+    inner: Arc<Inner<C::Client>>,
+}
+
+impl<C> Pool<C>
+where
+    C: Connect + Send,
+    C::Client: Send,
+{
+    /// Create a new pool with the default settings.
+    ///
+    /// Use a [`PoolBuilder`] to customize them.
+    #[must_use]
+    pub fn new() -> Self {
+        PoolBuilder::new().build()
+    }
+
+    /// Get a client for the given server address.
+    ///
+    /// Waits until fewer than the maximum amount of clients for the address are in use.
+    /// Reuses an idle client that logged in with the same password if possible,
+    /// otherwise connects and logs in with the given password.
+    /// Clients that have been idle for longer than the health check interval
+    /// are checked by sending an empty command and replaced if they do not respond.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if a new client is needed, but connecting or logging in fails.
+    pub async fn get<A, P>(&self, address: A, password: P) -> Result<Guard<C>, Error>
+    where
+        A: Into<String>,
+        P: AsRef<[u8]> + Send,
+    {
+        let address = address.into();
+        let password = Password::from(password.as_ref());
+        let permit = self
+            .inner
+            .semaphore(&address)
+            .acquire_owned()
+            .await
+            .map_err(|_| Error::Io(std::io::Error::other("Pool has been closed")))?;
+
+        while let Some((mut client, last_used)) = self.inner.take_idle(&address, &password) {
+            if last_used.elapsed() < self.inner.health_check_interval {
+                return Ok(Guard::new(
+                    client,
+                    address,
+                    password,
+                    self.inner.clone(),
+                    permit,
+                ));
+            }
+
+            debug!("Health-checking idle client for {address}");
+            match client
+                .run_with_timeout("", self.inner.health_check_timeout)
+                .await
+            {
+                Ok(_) => {
+                    return Ok(Guard::new(
+                        client,
+                        address,
+                        password,
+                        self.inner.clone(),
+                        permit,
+                    ));
+                }
+                Err(error) => warn!("Discarding unhealthy client for {address}: {error}"),
+            }
+        }
+
+        debug!("Connecting new client to {address}");
+        let client = C::connect(address.clone())
+            .await?
+            .login(&password.0)
+            .await?;
+        Ok(Guard::new(
+            client,
+            address,
+            password,
+            self.inner.clone(),
+            permit,
+        ))
+    }
+}
+
+impl<C> Clone for Pool<C>
+where
+    C: Connect,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<C> Default for Pool<C>
+where
+    C: Connect + Send,
+    C::Client: Send,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// State shared between the pool and its guards.
+#[derive(Debug)]
+struct Inner<T> {
+    max_size: NonZero<usize>,
+    health_check_interval: Duration,
+    health_check_timeout: Duration,
+    servers: Mutex<HashMap<String, Server<T>>>,
+}
+
+impl<T> Inner<T> {
+    fn semaphore(&self, address: &str) -> Arc<Semaphore> {
+        self.servers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(address.to_string())
+            .or_insert_with(|| Server::new(self.max_size))
+            .semaphore
+            .clone()
+    }
+
+    fn take_idle(&self, address: &str, password: &Password) -> Option<(T, Instant)> {
+        self.servers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_mut(address)
+            .and_then(|server| {
+                let index = server
+                    .idle
+                    .iter()
+                    .rposition(|idle| idle.password == *password)?;
+                Some(server.idle.remove(index))
+            })
+            .map(|idle| (idle.client, idle.last_used))
+    }
+
+    fn put_idle(&self, address: String, password: Password, client: T) {
+        self.servers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(address)
+            .or_insert_with(|| Server::new(self.max_size))
+            .idle
+            .push(Idle {
+                client,
+                password,
+                last_used: Instant::now(),
+            });
+    }
+}
+
+/// Clients of a single server.
+#[derive(Debug)]
+struct Server<T> {
+    semaphore: Arc<Semaphore>,
+    idle: Vec<Idle<T>>,
+}
+
+impl<T> Server<T> {
+    fn new(max_size: NonZero<usize>) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_size.get())),
+            idle: Vec::new(),
+        }
+    }
+}
+
+/// An idle client alongside the password it logged in with.
+#[derive(Debug)]
+struct Idle<T> {
+    client: T,
+    password: Password,
+    last_used: Instant,
+}
+
+/// A password, which is redacted in debug output.
+#[derive(Clone, Eq, PartialEq)]
+struct Password(Box<[u8]>);
+
+impl From<&[u8]> for Password {
+    fn from(password: &[u8]) -> Self {
+        Self(password.into())
+    }
+}
+
+impl Debug for Password {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use tokio::net::ToSocketAddrs;
+
+    use super::{Guard, Pool, PoolBuilder};
+    use crate::{Connect, Error, RCon};
+
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Debug)]
+    struct MockConnection;
+
+    #[derive(Debug)]
+    struct MockClient {
+        id: usize,
+        healthy: AtomicBool,
+    }
+
+    impl Connect for MockConnection {
+        type Client = MockClient;
+
+        async fn connect<T>(_address: T) -> Result<Self, Error>
+        where
+            T: ToSocketAddrs + Send,
+        {
+            Ok(Self)
+        }
+
+        async fn login<T>(self, password: T) -> Result<Self::Client, Error>
+        where
+            T: AsRef<[u8]> + Send,
+        {
+            if password.as_ref() == b"wrong" {
+                return Err(Error::AuthenticationFailed);
+            }
+
+            Ok(MockClient {
+                id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                healthy: AtomicBool::new(true),
+            })
+        }
+    }
+
+    impl RCon for MockClient {
+        async fn run<T>(&mut self, _command: T) -> Result<Vec<u8>, Error>
+        where
+            T: AsRef<[u8]> + Send,
+        {
+            if self.healthy.load(Ordering::Relaxed) {
+                Ok(self.id.to_string().into_bytes())
+            } else {
+                Err(Error::Disconnected)
+            }
+        }
+
+        async fn run_with_timeout<T>(
+            &mut self,
+            command: T,
+            _timeout: Duration,
+        ) -> Result<Vec<u8>, Error>
+        where
+            T: AsRef<[u8]> + Send,
+        {
+            self.run(command).await
+        }
+    }
+
+    async fn get(pool: &Pool<MockConnection>, password: &str) -> Guard<MockConnection> {
+        pool.get("127.0.0.1:2302", password)
+            .await
+            .expect("Mock login should succeed.")
+    }
+
+    #[tokio::test]
+    async fn test_reuse() {
+        let pool = Pool::new();
+        let id = get(&pool, "secret").await.id;
+        assert_eq!(get(&pool, "secret").await.id, id);
+    }
+
+    #[tokio::test]
+    async fn test_no_reuse_across_passwords() {
+        let pool = Pool::new();
+        let id = get(&pool, "secret").await.id;
+        assert_ne!(get(&pool, "other").await.id, id);
+        assert_eq!(get(&pool, "secret").await.id, id);
+    }
+
+    #[tokio::test]
+    async fn test_login_failure() {
+        let pool: Pool<MockConnection> = Pool::new();
+        assert!(matches!(
+            pool.get("127.0.0.1:2302", "wrong").await,
+            Err(Error::AuthenticationFailed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_health_check_eviction() {
+        let pool = PoolBuilder::new()
+            .health_check_interval(Duration::ZERO)
+            .build();
+        let guard = get(&pool, "secret").await;
+        let id = guard.id;
+        guard.healthy.store(false, Ordering::Relaxed);
+        drop(guard);
+        assert_ne!(get(&pool, "secret").await.id, id);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_keeps_healthy_client() {
+        let pool = PoolBuilder::new()
+            .health_check_interval(Duration::ZERO)
+            .build();
+        let id = get(&pool, "secret").await.id;
+        assert_eq!(get(&pool, "secret").await.id, id);
+    }
+
+    #[tokio::test]
+    async fn test_guard_discards_after_error() {
+        let pool = Pool::new();
+        let mut guard = get(&pool, "secret").await;
+        let id = guard.id;
+        guard.healthy.store(false, Ordering::Relaxed);
+        assert!(guard.run("status").await.is_err());
+        drop(guard);
+        assert_ne!(get(&pool, "secret").await.id, id);
+    }
+
+    #[tokio::test]
+    async fn test_guard_discard() {
+        let pool = Pool::new();
+        let mut guard = get(&pool, "secret").await;
+        let id = guard.id;
+        guard.discard();
+        drop(guard);
+        assert_ne!(get(&pool, "secret").await.id, id);
+    }
+}
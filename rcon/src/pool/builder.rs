@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::num::NonZero;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{Inner, Pool};
+use crate::Connect;
+
+const DEFAULT_MAX_SIZE: NonZero<usize> = NonZero::new(4).expect("Default max size is non-zero.");
+const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Builder for a [`Pool`] with custom settings.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PoolBuilder {
+    max_size: NonZero<usize>,
+    health_check_interval: Duration,
+    health_check_timeout: Duration,
+}
+
+impl PoolBuilder {
+    /// Creates a new builder with the default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_size: DEFAULT_MAX_SIZE,
+            health_check_interval: DEFAULT_HEALTH_CHECK_INTERVAL,
+            health_check_timeout: DEFAULT_HEALTH_CHECK_TIMEOUT,
+        }
+    }
+
+    /// Set the maximum amount of clients per server address.
+    #[must_use]
+    pub const fn max_size(mut self, max_size: NonZero<usize>) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Set the duration after which idle clients are health-checked before being handed out.
+    #[must_use]
+    pub const fn health_check_interval(mut self, interval: Duration) -> Self {
+        self.health_check_interval = interval;
+        self
+    }
+
+    /// Set the time to wait for the response to a health check.
+    #[must_use]
+    pub const fn health_check_timeout(mut self, timeout: Duration) -> Self {
+        self.health_check_timeout = timeout;
+        self
+    }
+
+    /// Create the pool.
+    #[must_use]
+    pub fn build<C>(self) -> Pool<C>
+    where
+        C: Connect,
+    {
+        Pool {
+            inner: Arc::new(Inner {
+                max_size: self.max_size,
+                health_check_interval: self.health_check_interval,
+                health_check_timeout: self.health_check_timeout,
+                servers: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+}
+
+impl Default for PoolBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
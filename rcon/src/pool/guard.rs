@@ -0,0 +1,128 @@
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::debug;
+use tokio::sync::OwnedSemaphorePermit;
+
+use super::{Inner, Password};
+use crate::{Connect, Error, RCon};
+
+/// A client borrowed from a [`Pool`](super::Pool).
+///
+/// The client is returned to the pool when the guard is dropped,
+/// unless a command failed, in which case the client is discarded.
+/// Commands are run through the guard's [`RCon`] implementation, so that failures are noticed.
+/// The client itself is only accessible by shared reference.
+#[derive(Debug)]
+pub struct Guard<C>
+where
+    C: Connect,
+{
+    client: Option<C::Client>,
+    address: String,
+    password: Password,
+    pool: Arc<Inner<C::Client>>,
+    broken: bool,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl<C> Guard<C>
+where
+    C: Connect,
+{
+    pub(super) const fn new(
+        client: C::Client,
+        address: String,
+        password: Password,
+        pool: Arc<Inner<C::Client>>,
+        permit: OwnedSemaphorePermit,
+    ) -> Self {
+        Self {
+            client: Some(client),
+            address,
+            password,
+            pool,
+            broken: false,
+            _permit: permit,
+        }
+    }
+
+    /// Returns the address of the server the client is connected to.
+    #[must_use]
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Discard the client instead of returning it to the pool.
+    pub const fn discard(&mut self) {
+        self.broken = true;
+    }
+
+    const fn client_mut(&mut self) -> &mut C::Client {
+        self.client
+            .as_mut()
+            .expect("Client should be present until drop.")
+    }
+
+    const fn inspect<T>(&mut self, result: Result<T, Error>) -> Result<T, Error> {
+        if result.is_err() {
+            self.discard();
+        }
+
+        result
+    }
+}
+
+impl<C> Deref for Guard<C>
+where
+    C: Connect,
+{
+    type Target = C::Client;
+
+    fn deref(&self) -> &Self::Target {
+        self.client
+            .as_ref()
+            .expect("Client should be present until drop.")
+    }
+}
+
+impl<C> RCon for Guard<C>
+where
+    C: Connect + Send,
+    C::Client: Send,
+{
+    async fn run<T>(&mut self, command: T) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]> + Send,
+    {
+        let result = self.client_mut().run(command).await;
+        self.inspect(result)
+    }
+
+    async fn run_with_timeout<T>(&mut self, command: T, timeout: Duration) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]> + Send,
+    {
+        let result = self.client_mut().run_with_timeout(command, timeout).await;
+        self.inspect(result)
+    }
+}
+
+impl<C> Drop for Guard<C>
+where
+    C: Connect,
+{
+    fn drop(&mut self) {
+        let Some(client) = self.client.take() else {
+            return;
+        };
+
+        if self.broken {
+            debug!("Discarding broken client for {}", self.address);
+        } else {
+            self.pool
+                .put_idle(self.address.clone(), self.password.clone(), client);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Resolution of server addresses.
+
+use std::future::Future;
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+
+use log::{info, warn};
+use tokio::net::{ToSocketAddrs, lookup_host};
+
+use crate::Error;
+
+/// Resolve the given address to its socket addresses.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if resolution fails or yields no socket addresses.
+pub async fn resolve<A>(address: A) -> Result<Vec<SocketAddr>, Error>
+where
+    A: ToSocketAddrs,
+{
+    let addresses: Vec<_> = lookup_host(address).await?.collect();
+
+    if addresses.is_empty() {
+        return Err(unresolved());
+    }
+
+    Ok(addresses)
+}
+
+/// Resolve the given address and try to connect to each resolved socket address in turn.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if resolution fails,
+/// or the error of the last connection attempt if no connection could be established.
+pub async fn connect_any<A, F, Fut, T>(address: A, mut connect: F) -> Result<T, Error>
+where
+    A: ToSocketAddrs,
+    F: FnMut(SocketAddr) -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut last_error = None;
+
+    for address in resolve(address).await? {
+        match connect(address).await {
+            Ok(connection) => {
+                info!("Connected to {address}");
+                return Ok(connection);
+            }
+            Err(error) => {
+                warn!("Connecting to {address} failed: {error}");
+                last_error.replace(error);
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(unresolved))
+}
+
+fn unresolved() -> Error {
+    Error::Io(std::io::Error::new(
+        ErrorKind::NotFound,
+        "Address did not resolve to any socket address",
+    ))
+}
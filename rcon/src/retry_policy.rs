@@ -0,0 +1,125 @@
+//! Policies for retrying failed connection attempts.
+
+use std::time::Duration;
+
+const DEFAULT_INITIAL_DELAY: Duration = Duration::from_secs(1);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_mins(1);
+const DEFAULT_FACTOR: u32 = 2;
+
+/// A policy for retrying failed connection attempts with exponential backoff.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RetryPolicy {
+    initial_delay: Duration,
+    max_delay: Duration,
+    factor: u32,
+    max_attempts: Option<u32>,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub const NEVER: Self = Self::new(Duration::ZERO, Duration::ZERO, 1, Some(0));
+
+    /// Create a new retry policy.
+    ///
+    /// The first retry is attempted after `initial_delay`.
+    /// Every subsequent delay is multiplied by `factor`, but never exceeds `max_delay`.
+    /// If `max_attempts` is `None`, retries are attempted indefinitely.
+    #[must_use]
+    pub const fn new(
+        initial_delay: Duration,
+        max_delay: Duration,
+        factor: u32,
+        max_attempts: Option<u32>,
+    ) -> Self {
+        Self {
+            initial_delay,
+            max_delay,
+            factor,
+            max_attempts,
+        }
+    }
+
+    /// Limit the maximum amount of retries.
+    #[must_use]
+    pub const fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// The delay before the first retry.
+    #[must_use]
+    pub const fn initial_delay(&self) -> Duration {
+        self.initial_delay
+    }
+
+    /// The maximum delay between two retries.
+    #[must_use]
+    pub const fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+
+    /// The factor by which the delay grows after each retry.
+    #[must_use]
+    pub const fn factor(&self) -> u32 {
+        self.factor
+    }
+
+    /// The maximum amount of retries, if any.
+    #[must_use]
+    pub const fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    /// Returns the delay before the given retry attempt, starting at `1`.
+    ///
+    /// Returns `None` if no further attempts shall be made.
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Option<Duration> {
+        if attempt == 0 || self.max_attempts.is_some_and(|max| attempt > max) {
+            return None;
+        }
+
+        Some(
+            self.factor
+                .checked_pow(attempt - 1)
+                .and_then(|factor| self.initial_delay.checked_mul(factor))
+                .map_or(self.max_delay, |delay| delay.min(self.max_delay)),
+        )
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_INITIAL_DELAY,
+            DEFAULT_MAX_DELAY,
+            DEFAULT_FACTOR,
+            None,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+
+    #[test]
+    fn test_exponential_backoff() {
+        let policy = RetryPolicy::new(Duration::from_secs(1), Duration::from_secs(10), 2, None);
+        assert_eq!(policy.delay(1), Some(Duration::from_secs(1)));
+        assert_eq!(policy.delay(2), Some(Duration::from_secs(2)));
+        assert_eq!(policy.delay(4), Some(Duration::from_secs(8)));
+        assert_eq!(policy.delay(5), Some(Duration::from_secs(10)));
+        assert_eq!(policy.delay(100), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_max_attempts() {
+        let policy = RetryPolicy::default().with_max_attempts(2);
+        assert!(policy.delay(2).is_some());
+        assert!(policy.delay(3).is_none());
+        assert!(RetryPolicy::NEVER.delay(1).is_none());
+    }
+}
@@ -0,0 +1,21 @@
+//! Common API of the server implementations.
+
+use std::future::Future;
+
+/// Handler for commands sent by authenticated clients.
+///
+/// Closures taking the command and returning a future of the response implement this trait.
+pub trait Handler: Send + Sync + 'static {
+    /// Handle a command and return the response to send to the client.
+    fn handle(&self, command: Vec<u8>) -> impl Future<Output = Vec<u8>> + Send;
+}
+
+impl<F, T> Handler for F
+where
+    F: Fn(Vec<u8>) -> T + Send + Sync + 'static,
+    T: Future<Output = Vec<u8>> + Send,
+{
+    fn handle(&self, command: Vec<u8>) -> impl Future<Output = Vec<u8>> + Send {
+        self(command)
+    }
+}
@@ -1,16 +1,29 @@
-//! Client implementation for the [`Source RCON`](https://developer.valvesoftware.com/wiki/Source_RCON_Protocol) protocol.
+//! Client and server implementation for the [`Source RCON`](https://developer.valvesoftware.com/wiki/Source_RCON_Protocol) protocol.
 
-pub use self::client::Client;
+pub use self::client::{Client, ClientBuilder};
+pub use self::connection::Connection;
+pub use self::packet::Packet;
 pub use self::quirks::Quirks;
+pub use self::server_data::ServerData;
 use crate::RCon;
 
+#[cfg(feature = "blocking")]
+pub(crate) mod blocking;
 mod client;
+#[cfg(feature = "source-codec")]
+pub mod codec;
+mod connection;
 mod packet;
 mod quirks;
+#[cfg(feature = "server")]
+pub mod server;
 mod server_data;
+mod session;
 mod util;
 
 /// Trait to identify `Source RCON` clients.
 pub trait Source: RCon {}
 
-impl Source for Client {}
+impl<T> Source for Client<T> where Self: RCon {}
+
+impl Source for crate::pool::Guard<Connection> {}
@@ -0,0 +1,411 @@
+use std::io::{Read, Write};
+use std::net::{Shutdown, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use log::debug;
+
+use super::packet::Packet;
+use super::quirks::Quirks;
+use super::session::Session;
+use super::util::{READ_SIZE, stream_error};
+use crate::Error;
+use crate::blocking::util::{DEFAULT_TIMEOUT, Deadline, connect_any, is_timeout, timed_out};
+use crate::blocking::{Connect, RCon};
+
+/// An unauthenticated blocking `Source RCON` connection.
+///
+/// Log in to obtain a [`Client`].
+#[derive(Debug)]
+pub struct Connection {
+    client: Client,
+}
+
+impl Connection {
+    /// Create a connection on an already connected TCP stream.
+    #[must_use]
+    pub const fn new(tcp_stream: TcpStream) -> Self {
+        Self {
+            client: Client::new(tcp_stream),
+        }
+    }
+
+    /// Set the default timeout for the login and commands of the resulting client.
+    ///
+    /// If `None`, the client waits for responses indefinitely.
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.client.set_timeout(timeout);
+        self
+    }
+
+    /// Set the idle timeout of the resulting client.
+    ///
+    /// See [`Client::set_idle_timeout`] for details.
+    #[must_use]
+    pub const fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.client.set_idle_timeout(idle_timeout);
+        self
+    }
+
+    /// Set the maximum accepted packet size of the resulting client.
+    ///
+    /// See [`Client::set_max_packet_size`] for details.
+    #[must_use]
+    pub const fn with_max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.client.set_max_packet_size(max_packet_size);
+        self
+    }
+
+    /// Enable a quirk on the resulting client.
+    #[must_use]
+    pub fn with_quirk(mut self, quirk: Quirks) -> Self {
+        self.client.enable_quirk(quirk);
+        self
+    }
+}
+
+impl Connect for Connection {
+    type Client = Client;
+
+    fn connect<T>(address: T) -> Result<Self, Error>
+    where
+        T: ToSocketAddrs,
+    {
+        connect_any(address, |address| {
+            Ok(TcpStream::connect_timeout(&address, DEFAULT_TIMEOUT)?)
+        })
+        .map(Self::new)
+    }
+
+    fn login<T>(mut self, password: T) -> Result<Self::Client, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        if self.client.login(password.as_ref())? {
+            Ok(self.client)
+        } else {
+            Err(Error::AuthenticationFailed)
+        }
+    }
+}
+
+impl From<TcpStream> for Connection {
+    fn from(tcp_stream: TcpStream) -> Self {
+        Self::new(tcp_stream)
+    }
+}
+
+/// An authenticated blocking `Source RCON` client.
+///
+/// Obtain a client by logging in on a [`Connection`].
+#[derive(Debug)]
+pub struct Client {
+    tcp_stream: TcpStream,
+    session: Session,
+}
+
+impl Client {
+    const fn new(tcp_stream: TcpStream) -> Self {
+        Self {
+            tcp_stream,
+            session: Session::new(),
+        }
+    }
+
+    /// Returns the default timeout for commands.
+    #[must_use]
+    pub const fn timeout(&self) -> Option<Duration> {
+        self.session.timeout()
+    }
+
+    /// Set the default timeout for commands.
+    ///
+    /// If `None`, the client waits for responses indefinitely.
+    ///
+    /// Late responses to timed out requests are discarded.
+    pub const fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.session.set_timeout(timeout);
+    }
+
+    /// Returns the idle timeout.
+    #[must_use]
+    pub const fn idle_timeout(&self) -> Duration {
+        self.session.idle_timeout()
+    }
+
+    /// Set the time to wait for further packets of a response
+    /// before considering it complete.
+    ///
+    /// Only applies if [`Quirks::IDLE_TIMEOUT`] is enabled.
+    pub const fn set_idle_timeout(&mut self, idle_timeout: Duration) {
+        self.session.set_idle_timeout(idle_timeout);
+    }
+
+    /// Returns the maximum accepted packet size.
+    ///
+    /// Unless set explicitly, this is [`Packet::MAX_SIZE`],
+    /// or 1 MiB if [`Quirks::LARGE_PACKETS`] is enabled.
+    #[must_use]
+    pub const fn max_packet_size(&self) -> usize {
+        self.session.max_packet_size()
+    }
+
+    /// Set the maximum accepted packet size.
+    ///
+    /// If the server sends a larger packet, [`Error::OversizedPacket`] is returned
+    /// and the connection is closed.
+    pub const fn set_max_packet_size(&mut self, max_packet_size: usize) {
+        self.session.set_max_packet_size(max_packet_size);
+    }
+
+    /// Returns the currently set quirks.
+    #[must_use]
+    pub const fn quirks(&self) -> Quirks {
+        self.session.quirks()
+    }
+
+    /// Enable a quirk on the client.
+    pub fn enable_quirk(&mut self, quirk: Quirks) {
+        self.session.enable_quirk(quirk);
+    }
+
+    /// Enable a quirk and return the client.
+    #[must_use]
+    pub fn with_quirk(mut self, quirk: Quirks) -> Self {
+        self.enable_quirk(quirk);
+        self
+    }
+
+    fn send(&mut self, bytes: &[u8], deadline: Deadline) -> Result<(), Error> {
+        self.tcp_stream.set_write_timeout(deadline.remaining()?)?;
+        self.tcp_stream
+            .write_all(bytes)
+            .map_err(|error| timed_out(stream_error(error)))
+    }
+
+    /// Read the next packet that is not a late response to an abandoned request.
+    ///
+    /// If an idle timeout is given and no data arrives within it, `None` is returned.
+    /// Received bytes are buffered until a packet is complete,
+    /// so that a timeout does not corrupt the stream.
+    fn read_packet(
+        &mut self,
+        deadline: Deadline,
+        idle_timeout: Option<Duration>,
+    ) -> Result<Option<Packet>, Error> {
+        let mut chunk = [0; READ_SIZE];
+
+        loop {
+            match self.session.decode() {
+                Ok(Some(packet)) => return Ok(Some(packet)),
+                Ok(None) => (),
+                Err(Error::Disconnected) => return Err(Error::Disconnected),
+                Err(error) => {
+                    if let Err(error) = self.tcp_stream.shutdown(Shutdown::Both) {
+                        debug!("Shutting down the TCP stream failed: {error}");
+                    }
+
+                    return Err(error);
+                }
+            }
+
+            let timeout = match (deadline.remaining()?, idle_timeout) {
+                (Some(remaining), Some(idle_timeout)) => Some(remaining.min(idle_timeout)),
+                (remaining, idle_timeout) => remaining.or(idle_timeout),
+            };
+            self.tcp_stream.set_read_timeout(timeout)?;
+
+            match self.tcp_stream.read(&mut chunk) {
+                Ok(0) => return Err(Error::Disconnected),
+                Ok(size) => self.session.input().extend_from_slice(&chunk[..size]),
+                Err(error) if is_timeout(&error) => {
+                    deadline.remaining()?;
+
+                    if idle_timeout.is_some() {
+                        return Ok(None);
+                    }
+                }
+                Err(error) => return Err(stream_error(error)),
+            }
+        }
+    }
+
+    /// Perform a login.
+    ///
+    /// Returns `true` if the server accepted the password.
+    fn login(&mut self, password: &[u8]) -> Result<bool, Error> {
+        let deadline = Deadline::after(self.session.timeout());
+        let bytes = self.session.login(password)?;
+        self.send(&bytes, deadline)?;
+
+        loop {
+            let packet = self.read_packet(deadline, None)?.ok_or(Error::TimedOut)?;
+
+            if let Some(success) = self.session.login_response(&packet) {
+                return Ok(success);
+            }
+        }
+    }
+
+    fn run_inner(&mut self, command: &[u8], deadline: Deadline) -> Result<Vec<u8>, Error> {
+        let bytes = self.session.command(command)?;
+        self.send(&bytes, deadline)?;
+
+        loop {
+            let idle_timeout = self.session.pending_idle_timeout();
+
+            let Some(packet) = self.read_packet(deadline, idle_timeout)? else {
+                return Ok(self.session.idle());
+            };
+
+            if let Some(response) = self.session.command_response(packet)? {
+                return Ok(response);
+            }
+        }
+    }
+}
+
+impl RCon for Client {
+    fn run<T>(&mut self, command: T) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.run_inner(command.as_ref(), Deadline::after(self.session.timeout()))
+    }
+
+    fn run_with_timeout<T>(&mut self, command: T, timeout: Duration) -> Result<Vec<u8>, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.run_inner(command.as_ref(), Deadline::after(Some(timeout)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpListener, TcpStream};
+    use std::thread::{JoinHandle, spawn};
+    use std::time::Duration;
+
+    use super::{Client, Connection};
+    use crate::Error;
+    use crate::blocking::{Connect, RCon};
+    use crate::source::packet::{AUTH_FAILURE, Packet};
+    use crate::source::server_data::ServerData;
+
+    /// Answer logins and echo commands on a fake server on a background thread.
+    ///
+    /// Responses are split into packets of at most 4 bytes.
+    /// If `respond` is `false`, commands are not answered.
+    fn spawn_server(respond: bool) -> (SocketAddr, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let handle = spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            serve(stream, respond);
+        });
+        (address, handle)
+    }
+
+    fn serve(mut stream: TcpStream, respond: bool) {
+        let mut input = Vec::new();
+        let mut chunk = [0; 4096];
+
+        loop {
+            while let Some((packet, size)) = Packet::decode_with_limit(&input, 4096).unwrap() {
+                input.drain(..size);
+
+                for response in respond_to(packet, respond) {
+                    let bytes: Vec<u8> = response.try_into().unwrap();
+                    stream.write_all(&bytes).unwrap();
+                }
+            }
+
+            match stream.read(&mut chunk) {
+                Ok(0) | Err(_) => return,
+                Ok(size) => input.extend_from_slice(&chunk[..size]),
+            }
+        }
+    }
+
+    fn respond_to(packet: Packet, respond: bool) -> Vec<Packet> {
+        match packet.typ {
+            ServerData::Auth => {
+                let id = if packet.payload == b"secret" {
+                    packet.id
+                } else {
+                    AUTH_FAILURE
+                };
+                vec![
+                    Packet::new(packet.id, ServerData::ResponseValue, Vec::new(), [0, 0]),
+                    Packet::new(
+                        id,
+                        ServerData::ExecCommandOrAuthResponse,
+                        Vec::new(),
+                        [0, 0],
+                    ),
+                ]
+            }
+            _ if !respond => Vec::new(),
+            ServerData::ExecCommandOrAuthResponse => packet
+                .payload
+                .chunks(4)
+                .map(|chunk| {
+                    Packet::new(packet.id, ServerData::ResponseValue, chunk.to_vec(), [0, 0])
+                })
+                .collect(),
+            ServerData::ResponseValue => vec![packet],
+        }
+    }
+
+    fn login(address: SocketAddr, password: &str) -> Result<Client, Error> {
+        Connection::connect(address)?
+            .with_timeout(Some(Duration::from_secs(5)))
+            .login(password)
+    }
+
+    #[test]
+    fn test_login_and_run() {
+        let (address, handle) = spawn_server(true);
+        let mut client = login(address, "secret").unwrap();
+        assert_eq!(client.run_utf8("list").unwrap(), "list");
+        drop(client);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_login_rejected() {
+        let (address, handle) = spawn_server(true);
+        assert!(matches!(
+            login(address, "wrong"),
+            Err(Error::AuthenticationFailed)
+        ));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_response_split_across_packets() {
+        let (address, handle) = spawn_server(true);
+        let mut client = login(address, "secret").unwrap();
+        assert_eq!(
+            client.run_utf8("say Hello, World").unwrap(),
+            "say Hello, World"
+        );
+        assert_eq!(client.run_utf8("help").unwrap(), "help");
+        drop(client);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_run_with_timeout() {
+        let (address, handle) = spawn_server(false);
+        let mut client = login(address, "secret").unwrap();
+        assert!(matches!(
+            client.run_with_timeout("list", Duration::from_millis(100)),
+            Err(Error::TimedOut)
+        ));
+        drop(client);
+        handle.join().unwrap();
+    }
+}
@@ -1,44 +1,113 @@
 use std::net::SocketAddr;
+use std::time::Duration;
 
-use log::{debug, error, trace};
-use rand::random;
-use tokio::io::AsyncWriteExt;
+use log::debug;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::TcpStream;
 
+pub use self::builder::ClientBuilder;
 use super::packet::Packet;
 use super::quirks::Quirks;
-use super::server_data::ServerData;
-use super::util::invalid_data;
-use crate::RCon;
+use super::session::Session;
+use super::util::{READ_SIZE, stream_error};
+use crate::timeout::timeout;
+use crate::{Error, RCon};
 
-/// A Source `RCON` client.
+mod builder;
+
+/// An authenticated Source `RCON` client.
+///
+/// Obtain a client by logging in on a [`Connection`](super::Connection).
+///
+/// The client communicates over a TCP stream by default,
+/// but can use any other bidirectional byte stream as transport, such as a TLS tunnel.
+/// Timeouts require the transport to be driven by the `tokio` runtime.
 #[derive(Debug)]
-pub struct Client {
-    tcp_stream: TcpStream,
-    quirks: Quirks,
-    buffer: Vec<Packet>,
+pub struct Client<T = TcpStream> {
+    transport: T,
+    session: Session,
 }
 
 impl Client {
-    /// Creates a new client instance.
+    /// Returns a builder to configure a new connection.
     #[must_use]
-    pub const fn new(tcp_stream: TcpStream) -> Self {
+    pub const fn builder() -> ClientBuilder {
+        ClientBuilder::new()
+    }
+
+    /// Returns the address of the server the client is connected to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the address of the TCP stream cannot be determined.
+    pub fn peer_addr(&self) -> Result<SocketAddr, Error> {
+        Ok(self.transport.peer_addr()?)
+    }
+}
+
+impl<T> Client<T> {
+    pub(super) const fn new(transport: T) -> Self {
         Self {
-            tcp_stream,
-            quirks: Quirks::NONE,
-            buffer: Vec::new(),
+            transport,
+            session: Session::new(),
         }
     }
 
+    /// Returns the default timeout for commands.
+    #[must_use]
+    pub const fn timeout(&self) -> Option<Duration> {
+        self.session.timeout()
+    }
+
+    /// Set the default timeout for commands.
+    ///
+    /// If `None`, the client waits for responses indefinitely.
+    ///
+    /// Late responses to timed out requests are discarded.
+    pub const fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.session.set_timeout(timeout);
+    }
+
+    /// Returns the idle timeout.
+    #[must_use]
+    pub const fn idle_timeout(&self) -> Duration {
+        self.session.idle_timeout()
+    }
+
+    /// Set the time to wait for further packets of a response
+    /// before considering it complete.
+    ///
+    /// Only applies if [`Quirks::IDLE_TIMEOUT`] is enabled.
+    pub const fn set_idle_timeout(&mut self, idle_timeout: Duration) {
+        self.session.set_idle_timeout(idle_timeout);
+    }
+
+    /// Returns the maximum accepted packet size.
+    ///
+    /// Unless set explicitly, this is [`Packet::MAX_SIZE`],
+    /// or 1 MiB if [`Quirks::LARGE_PACKETS`] is enabled.
+    #[must_use]
+    pub const fn max_packet_size(&self) -> usize {
+        self.session.max_packet_size()
+    }
+
+    /// Set the maximum accepted packet size.
+    ///
+    /// If the server sends a larger packet, [`Error::OversizedPacket`] is returned
+    /// and the connection is closed.
+    pub const fn set_max_packet_size(&mut self, max_packet_size: usize) {
+        self.session.set_max_packet_size(max_packet_size);
+    }
+
     /// Returns the currently set quirks.
     #[must_use]
     pub const fn quirks(&self) -> Quirks {
-        self.quirks
+        self.session.quirks()
     }
 
     /// Enable a quirk on the client.
     pub fn enable_quirk(&mut self, quirk: Quirks) {
-        self.quirks.insert(quirk);
+        self.session.enable_quirk(quirk);
     }
 
     /// Enable a quirk and return the client.
@@ -48,89 +117,228 @@ impl Client {
         self
     }
 
-    async fn send(&mut self, packet: Packet) -> std::io::Result<()> {
-        let bytes: Vec<_> = packet.try_into().map_err(invalid_data)?;
-        debug!("Sending bytes: {bytes:?}");
-        self.tcp_stream.write_all(bytes.as_slice()).await
+    /// Returns a reference to the underlying transport.
+    #[must_use]
+    pub const fn transport(&self) -> &T {
+        &self.transport
     }
+}
 
-    async fn read_responses(&mut self, id: i32) -> std::io::Result<Vec<u8>> {
+impl<T> Client<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    async fn send(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.transport.write_all(bytes).await.map_err(stream_error)
+    }
+
+    /// Read the next packet that is not a late response to an abandoned request.
+    ///
+    /// Received bytes are buffered until a packet is complete,
+    /// so that cancelling this future does not corrupt the stream.
+    async fn read_packet(&mut self) -> Result<Packet, Error> {
         loop {
-            let packet = Packet::read_from(&mut self.tcp_stream).await?;
-
-            match packet.typ {
-                ServerData::ExecCommandOrAuthResponse => return Ok(packet.payload),
-                ServerData::ResponseValue => {
-                    if packet.typ == ServerData::ResponseValue {
-                        // Check for sentinel ID, which is one ahead of the command ID.
-                        if packet.id == id.wrapping_add(1) {
-                            debug!("Received sentinel packet");
-                            return Ok(self.collect_buffer());
-                        }
-
-                        packet.validate(id, self.quirks)?;
-                        debug!("Received data packet");
-                        self.buffer.push(packet);
+            match self.session.decode() {
+                Ok(Some(packet)) => return Ok(packet),
+                Ok(None) => (),
+                Err(Error::Disconnected) => return Err(Error::Disconnected),
+                Err(error) => {
+                    if let Err(error) = self.transport.shutdown().await {
+                        debug!("Shutting down the transport failed: {error}");
                     }
+
+                    return Err(error);
                 }
-                ServerData::Auth => {
-                    error!("Received unexpected packet type: {:?}", ServerData::Auth);
-                    trace!("Packet: {packet:?}");
-                }
+            }
+
+            let input = self.session.input();
+            input.reserve(READ_SIZE);
+
+            if self.transport.read_buf(input).await.map_err(stream_error)? == 0 {
+                return Err(Error::Disconnected);
             }
         }
     }
 
-    fn collect_buffer(&mut self) -> Vec<u8> {
-        self.buffer
-            .drain(..)
-            .flat_map(|response| response.payload)
-            .collect()
+    /// Perform a login.
+    ///
+    /// Returns `true` if the server accepted the password.
+    pub(super) async fn login(&mut self, password: &[u8]) -> Result<bool, Error> {
+        timeout(self.session.timeout(), self.login_inner(password)).await
     }
-}
 
-impl From<TcpStream> for Client {
-    fn from(tcp_stream: TcpStream) -> Self {
-        Self::new(tcp_stream)
+    async fn login_inner(&mut self, password: &[u8]) -> Result<bool, Error> {
+        let bytes = self.session.login(password)?;
+        self.send(&bytes).await?;
+
+        loop {
+            let packet = self.read_packet().await?;
+
+            if let Some(success) = self.session.login_response(&packet) {
+                return Ok(success);
+            }
+        }
+    }
+
+    async fn run_inner(&mut self, command: &[u8]) -> Result<Vec<u8>, Error> {
+        let bytes = self.session.command(command)?;
+        self.send(&bytes).await?;
+
+        loop {
+            let packet = if let Some(idle_timeout) = self.session.pending_idle_timeout() {
+                if let Ok(packet) = tokio::time::timeout(idle_timeout, self.read_packet()).await {
+                    packet?
+                } else {
+                    return Ok(self.session.idle());
+                }
+            } else {
+                self.read_packet().await?
+            };
+
+            if let Some(response) = self.session.command_response(packet)? {
+                return Ok(response);
+            }
+        }
     }
 }
 
-impl RCon for Client {
-    async fn connect<T>(address: T) -> std::io::Result<Self>
+impl<S> RCon for Client<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    async fn run<T>(&mut self, args: T) -> Result<Vec<u8>, Error>
     where
-        T: Into<SocketAddr> + Send,
+        T: AsRef<[u8]> + Send,
     {
-        TcpStream::connect(address.into()).await.map(Self::from)
+        timeout(self.session.timeout(), self.run_inner(args.as_ref())).await
     }
 
-    async fn login<T>(&mut self, password: T) -> std::io::Result<bool>
+    async fn run_with_timeout<T>(&mut self, args: T, duration: Duration) -> Result<Vec<u8>, Error>
     where
         T: AsRef<[u8]> + Send,
     {
-        self.send(Packet::login(random(), password.as_ref()))
-            .await?;
-        let mut packet;
+        timeout(Some(duration), self.run_inner(args.as_ref())).await
+    }
+}
 
-        loop {
-            debug!("Reading response packet.");
-            packet = Packet::read_from(&mut self.tcp_stream).await?;
-            if packet.typ == ServerData::ExecCommandOrAuthResponse {
-                break;
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::io::{AsyncWriteExt, DuplexStream, duplex};
+
+    use crate::source::packet::{AUTH_FAILURE, Packet};
+    use crate::source::server_data::ServerData;
+    use crate::source::{Connection, Quirks};
+    use crate::{Error, RCon};
+
+    /// Answer logins and echo commands on the server side of an in-memory pipe.
+    ///
+    /// Responses are split into packets of at most 4096 bytes.
+    /// If `sentinel` is `false`, sentinel packets are not echoed.
+    async fn serve(mut stream: DuplexStream, password: &[u8], sentinel: bool) {
+        while let Ok(packet) = Packet::read_from_with_limit(&mut stream, 1 << 16).await {
+            let responses = match packet.typ {
+                ServerData::Auth => {
+                    let id = if packet.payload == password {
+                        packet.id
+                    } else {
+                        AUTH_FAILURE
+                    };
+                    vec![
+                        Packet::new(packet.id, ServerData::ResponseValue, Vec::new(), [0, 0]),
+                        Packet::new(
+                            id,
+                            ServerData::ExecCommandOrAuthResponse,
+                            Vec::new(),
+                            [0, 0],
+                        ),
+                    ]
+                }
+                ServerData::ExecCommandOrAuthResponse => packet
+                    .payload
+                    .chunks(4096)
+                    .map(|chunk| {
+                        Packet::new(packet.id, ServerData::ResponseValue, chunk.to_vec(), [0, 0])
+                    })
+                    .collect(),
+                ServerData::ResponseValue if sentinel => vec![packet],
+                ServerData::ResponseValue => Vec::new(),
+            };
+
+            for response in responses {
+                let bytes: Vec<u8> = response.try_into().unwrap();
+                stream.write_all(&bytes).await.unwrap();
             }
         }
+    }
 
-        Ok(packet.id >= 0)
+    #[tokio::test]
+    async fn test_duplex_transport() {
+        let (client, server) = duplex(4096);
+        tokio::spawn(serve(server, b"secret", true));
+
+        let mut client = Connection::new(client).login("secret").await.unwrap();
+        assert_eq!(client.run_utf8("list").await.unwrap(), "list");
+        assert_eq!(client.run_utf8("help").await.unwrap(), "help");
     }
 
-    async fn run<T>(&mut self, args: T) -> std::io::Result<Vec<u8>>
-    where
-        T: AsRef<[u8]> + Send,
-    {
-        let command = Packet::command(random(), args.as_ref());
-        let command_id = command.id;
-        let sentinel = command.sentinel();
-        self.send(command).await?;
-        self.send(sentinel).await?;
-        self.read_responses(command_id).await
+    #[tokio::test]
+    async fn test_duplex_transport_wrong_password() {
+        let (client, server) = duplex(4096);
+        tokio::spawn(serve(server, b"secret", true));
+
+        assert!(matches!(
+            Connection::new(client).login("wrong").await,
+            Err(Error::AuthenticationFailed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_short_packets_without_sentinel() {
+        let (client, server) = duplex(4096);
+        tokio::spawn(serve(server, b"secret", false));
+
+        let mut client = Connection::new(client)
+            .login("secret")
+            .await
+            .unwrap()
+            .with_quirk(Quirks::SHORT_PACKETS | Quirks::LARGE_PACKETS);
+        let command = "a".repeat(5000);
+        assert_eq!(client.run_utf8(&command).await.unwrap(), command);
+        assert_eq!(client.run_utf8("list").await.unwrap(), "list");
+    }
+
+    #[tokio::test]
+    async fn test_idle_timeout_without_sentinel() {
+        let (client, server) = duplex(4096);
+        tokio::spawn(serve(server, b"secret", false));
+
+        let mut client = Connection::new(client)
+            .login("secret")
+            .await
+            .unwrap()
+            .with_quirk(Quirks::IDLE_TIMEOUT | Quirks::LARGE_PACKETS);
+        client.set_idle_timeout(Duration::from_millis(50));
+        let command = "a".repeat(8192);
+        assert_eq!(client.run_utf8(&command).await.unwrap(), command);
+        assert_eq!(client.run_utf8("list").await.unwrap(), "list");
+    }
+
+    #[tokio::test]
+    async fn test_oversized_packet_closes_connection() {
+        let (client, server) = duplex(8192);
+        tokio::spawn(serve(server, b"secret", true));
+
+        let mut client = Connection::new(client).login("secret").await.unwrap();
+        client.set_max_packet_size(1024);
+        assert!(matches!(
+            client.run(&[b'a'; 2048]).await,
+            Err(Error::OversizedPacket {
+                size: 2058,
+                max_size: 1024
+            })
+        ));
+        assert!(matches!(client.run("list").await, Err(Error::Disconnected)));
     }
 }
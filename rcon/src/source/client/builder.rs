@@ -0,0 +1,212 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use log::{debug, warn};
+use tokio::net::{TcpSocket, TcpStream, ToSocketAddrs, lookup_host};
+use tokio::time::sleep;
+
+use super::Client;
+use crate::resolve::connect_any;
+use crate::source::{Connection, Quirks};
+use crate::timeout::timeout;
+use crate::{Error, RetryPolicy};
+
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Builder for a `Source RCON` [`Connection`] with custom settings.
+///
+/// Log in on the connection to obtain a [`Client`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ClientBuilder {
+    bind_address: Option<SocketAddr>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    max_packet_size: Option<usize>,
+    send_buffer_size: Option<u32>,
+    recv_buffer_size: Option<u32>,
+    quirks: Quirks,
+    retry_policy: RetryPolicy,
+}
+
+impl ClientBuilder {
+    /// Creates a new builder with the default settings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            bind_address: None,
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            timeout: Some(DEFAULT_TIMEOUT),
+            idle_timeout: None,
+            max_packet_size: None,
+            send_buffer_size: None,
+            recv_buffer_size: None,
+            quirks: Quirks::NONE,
+            retry_policy: RetryPolicy::NEVER,
+        }
+    }
+
+    /// Set the local address to bind the TCP socket to.
+    #[must_use]
+    pub const fn bind_address(mut self, bind_address: SocketAddr) -> Self {
+        self.bind_address = Some(bind_address);
+        self
+    }
+
+    /// Set the timeout for connecting to each resolved address.
+    ///
+    /// If `None`, the operating system's timeout applies.
+    #[must_use]
+    pub const fn connect_timeout(mut self, connect_timeout: Option<Duration>) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Set the default timeout for the login and commands.
+    ///
+    /// If `None`, the client waits for responses indefinitely.
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the time to wait for further packets of a response before considering it complete.
+    ///
+    /// Only applies if [`Quirks::IDLE_TIMEOUT`] is enabled.
+    #[must_use]
+    pub const fn idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = Some(idle_timeout);
+        self
+    }
+
+    /// Set the maximum accepted packet size.
+    ///
+    /// Defaults to [`Packet::MAX_SIZE`](crate::source::Packet::MAX_SIZE),
+    /// or 1 MiB if [`Quirks::LARGE_PACKETS`] is enabled.
+    #[must_use]
+    pub const fn max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.max_packet_size = Some(max_packet_size);
+        self
+    }
+
+    /// Set the size of the socket's send buffer.
+    ///
+    /// Defaults to the operating system's setting.
+    #[must_use]
+    pub const fn send_buffer_size(mut self, size: u32) -> Self {
+        self.send_buffer_size = Some(size);
+        self
+    }
+
+    /// Set the size of the socket's receive buffer.
+    ///
+    /// Defaults to the operating system's setting.
+    #[must_use]
+    pub const fn recv_buffer_size(mut self, size: u32) -> Self {
+        self.recv_buffer_size = Some(size);
+        self
+    }
+
+    /// Enable a quirk on the client.
+    #[must_use]
+    pub fn quirk(mut self, quirk: Quirks) -> Self {
+        self.quirks.insert(quirk);
+        self
+    }
+
+    /// Set the policy for retrying failed connection attempts.
+    ///
+    /// Defaults to [`RetryPolicy::NEVER`].
+    #[must_use]
+    pub const fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Connect to the given server.
+    ///
+    /// Each address the server address resolves to is tried in turn.
+    /// If none of them accepts the connection,
+    /// the attempt is retried according to the retry policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if resolving the address fails,
+    /// or the error of the last connection attempt, if all attempts failed.
+    pub async fn connect<T>(self, address: T) -> Result<Connection, Error>
+    where
+        T: ToSocketAddrs,
+    {
+        let addresses: Vec<_> = lookup_host(address).await?.collect();
+        let mut attempt = 0;
+
+        loop {
+            match connect_any(addresses.as_slice(), |address| {
+                timeout(self.connect_timeout, self.try_connect(address))
+            })
+            .await
+            {
+                Ok(tcp_stream) => return Ok(self.build(tcp_stream)),
+                Err(error) => {
+                    attempt += 1;
+                    let Some(delay) = self.retry_policy.delay(attempt) else {
+                        return Err(error);
+                    };
+
+                    warn!("Connection attempt #{attempt} failed: {error}");
+                    debug!("Retrying in {delay:?}");
+                    sleep(delay).await;
+                }
+            }
+        }
+    }
+
+    /// Build a connection on an already connected transport, such as a TCP stream.
+    ///
+    /// Socket options of the builder are not applied to the transport.
+    #[must_use]
+    pub fn build<T>(self, transport: T) -> Connection<T> {
+        let mut client = Client::new(transport).with_quirk(self.quirks);
+        client.set_timeout(self.timeout);
+
+        if let Some(idle_timeout) = self.idle_timeout {
+            client.set_idle_timeout(idle_timeout);
+        }
+
+        if let Some(max_packet_size) = self.max_packet_size {
+            client.set_max_packet_size(max_packet_size);
+        }
+
+        Connection::from_client(client)
+    }
+
+    async fn try_connect(&self, address: SocketAddr) -> Result<TcpStream, Error> {
+        let socket = if address.is_ipv4() {
+            TcpSocket::new_v4()?
+        } else {
+            TcpSocket::new_v6()?
+        };
+
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size)?;
+        }
+
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size)?;
+        }
+
+        if let Some(bind_address) = self.bind_address {
+            socket.bind(bind_address)?;
+        }
+
+        Ok(socket.connect(address).await?)
+    }
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
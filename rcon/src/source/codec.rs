@@ -0,0 +1,107 @@
+//! [`tokio_util::codec`] implementation for framing a byte stream into `Source RCON` packets.
+
+use std::io::ErrorKind;
+
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use super::Packet;
+use crate::Error;
+
+/// Codec to decode and encode [`Packet`]s on a byte stream.
+///
+/// Since the packet format is the same in both directions,
+/// the codec can be used on the client side as well as on the server side.
+///
+/// Packets exceeding [`Packet::MAX_SIZE`] are rejected unless a larger limit is set.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Codec {
+    max_size: usize,
+}
+
+impl Codec {
+    /// Create a new codec accepting packets of up to [`Packet::MAX_SIZE`] bytes.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_max_size(Packet::MAX_SIZE)
+    }
+
+    /// Create a new codec accepting packets of up to `max_size` bytes.
+    #[must_use]
+    pub const fn with_max_size(max_size: usize) -> Self {
+        Self { max_size }
+    }
+
+    /// Returns the maximum accepted packet size.
+    #[must_use]
+    pub const fn max_size(&self) -> usize {
+        self.max_size
+    }
+}
+
+impl Default for Codec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Decoder for Codec {
+    type Item = Packet;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let Some((packet, size)) = Packet::decode_with_limit(src, self.max_size)? else {
+            return Ok(None);
+        };
+
+        src.advance(size);
+        Ok(Some(packet))
+    }
+}
+
+impl Encoder<Packet> for Codec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Packet, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let bytes: Vec<u8> = item
+            .try_into()
+            .map_err(|error| std::io::Error::new(ErrorKind::InvalidInput, error))?;
+        dst.extend_from_slice(&bytes);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    use super::Codec;
+    use crate::source::Packet;
+
+    #[test]
+    fn test_codec() {
+        let mut codec = Codec::new();
+        let mut buffer = BytesMut::new();
+        codec
+            .encode(Packet::login(1, b"secret"), &mut buffer)
+            .unwrap();
+        codec
+            .encode(Packet::command(2, b"list"), &mut buffer)
+            .unwrap();
+        let mut tail = buffer.split_off(buffer.len() - 3);
+
+        assert_eq!(
+            codec.decode(&mut buffer).unwrap(),
+            Some(Packet::login(1, b"secret"))
+        );
+        assert_eq!(codec.decode(&mut buffer).unwrap(), None);
+
+        buffer.unsplit(tail.split());
+        assert_eq!(
+            codec.decode(&mut buffer).unwrap(),
+            Some(Packet::command(2, b"list"))
+        );
+        assert!(buffer.is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+use std::net::SocketAddr;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpStream, ToSocketAddrs};
+
+use super::{Client, ClientBuilder};
+use crate::{Connect, Error};
+
+/// An unauthenticated connection to a `Source RCON` server.
+///
+/// Log in to obtain a [`Client`] that can run commands.
+///
+/// Connections over transports other than TCP, such as TLS tunnels or in-memory pipes,
+/// can be created with [`Connection::new`].
+#[derive(Debug)]
+pub struct Connection<T = TcpStream> {
+    client: Client<T>,
+}
+
+impl<T> Connection<T> {
+    /// Creates a new connection on an established transport.
+    #[must_use]
+    pub const fn new(transport: T) -> Self {
+        Self::from_client(Client::new(transport))
+    }
+
+    pub(super) const fn from_client(client: Client<T>) -> Self {
+        Self { client }
+    }
+}
+
+impl Connection {
+    /// Returns the address of the server the connection has been established to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the address of the TCP stream cannot be determined.
+    pub fn peer_addr(&self) -> Result<SocketAddr, Error> {
+        self.client.peer_addr()
+    }
+}
+
+impl<T> Connection<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    /// Perform a login, consuming the connection.
+    ///
+    /// See [`Connect::login`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AuthenticationFailed`] if the server rejected the password.
+    /// Returns another [`Error`] if any I/O or protocol errors occurred.
+    pub async fn login<P>(mut self, password: P) -> Result<Client<T>, Error>
+    where
+        P: AsRef<[u8]>,
+    {
+        if self.client.login(password.as_ref()).await? {
+            Ok(self.client)
+        } else {
+            Err(Error::AuthenticationFailed)
+        }
+    }
+}
+
+impl<T> From<T> for Connection<T> {
+    fn from(transport: T) -> Self {
+        Self::new(transport)
+    }
+}
+
+impl Connect for Connection {
+    type Client = Client;
+
+    async fn connect<T>(address: T) -> Result<Self, Error>
+    where
+        T: ToSocketAddrs + Send,
+    {
+        ClientBuilder::new().connect(address).await
+    }
+
+    async fn login<T>(self, password: T) -> Result<Self::Client, Error>
+    where
+        T: AsRef<[u8]> + Send,
+    {
+        Self::login(self, password).await
+    }
+}
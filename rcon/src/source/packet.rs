@@ -2,17 +2,23 @@ use std::num::TryFromIntError;
 
 use log::{debug, trace, warn};
 use num_traits::FromPrimitive;
-use tokio::io::AsyncReadExt;
-use tokio::net::TcpStream;
+use tokio::io::{AsyncRead, AsyncReadExt};
 
 use super::quirks::Quirks;
 use super::server_data::ServerData;
-use super::util::invalid_data;
+use super::util::stream_error;
+use crate::Error;
 
 const TERMINATOR: [u8; 2] = [0, 0];
-const OFFSET: usize = 10;
+pub(super) const OFFSET: usize = 10;
 
-/// A source RCON packet.
+/// Packet ID of an authentication response rejecting the password.
+pub const AUTH_FAILURE: i32 = -1;
+
+/// A `Source RCON` packet.
+///
+/// Packets can be decoded from a byte buffer with [`Packet::decode`]
+/// and encoded by converting them into a `Vec<u8>`.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct Packet {
     pub(crate) id: i32,
@@ -22,6 +28,11 @@ pub struct Packet {
 }
 
 impl Packet {
+    /// Maximum size of a packet, excluding its size field, as specified by the protocol.
+    ///
+    /// Some servers, such as Minecraft, Rust and Factorio, send larger packets.
+    pub const MAX_SIZE: usize = 4096;
+
     /// Create a new packet.
     #[must_use]
     pub const fn new(id: i32, typ: ServerData, payload: Vec<u8>, terminator: [u8; 2]) -> Self {
@@ -61,55 +72,131 @@ impl Packet {
         )
     }
 
-    /// Read the packet from a TCP stream.
+    /// Read the packet from a stream.
+    ///
+    /// # Errors
+    ///
+    /// Return an [`Error`] if reading from the stream fails at some point.
+    /// If the server closed the connection, [`Error::Disconnected`] is returned.
+    /// If the packet's header is invalid, [`Error::MalformedPacket`] is returned.
+    /// If the packet exceeds [`Packet::MAX_SIZE`], [`Error::OversizedPacket`] is returned.
+    pub async fn read_from<T>(source: &mut T) -> Result<Self, Error>
+    where
+        T: AsyncRead + Unpin,
+    {
+        Self::read_from_with_limit(source, Self::MAX_SIZE).await
+    }
+
+    /// Read the packet from a stream, accepting packets of up to `max_size` bytes.
     ///
     /// # Errors
     ///
-    /// Return an [`std::io::Error`] if reading from the stream fails at some point.
-    pub async fn read_from(source: &mut TcpStream) -> std::io::Result<Self> {
+    /// Behaves like [`Packet::read_from`].
+    pub async fn read_from_with_limit<T>(source: &mut T, max_size: usize) -> Result<Self, Error>
+    where
+        T: AsyncRead + Unpin,
+    {
         let mut buffer = [0; size_of::<i32>()];
-        debug!("Reading payload size.");
-        source.read_exact(&mut buffer).await?;
-        let size: usize = i32::from_le_bytes(buffer)
-            .try_into()
-            .map_err(invalid_data)?;
-        trace!("Packet size is {size}.");
+        debug!("Reading packet size.");
+        source.read_exact(&mut buffer).await.map_err(stream_error)?;
+        let mut body = vec![0; body_size(buffer, max_size)?];
+        debug!("Reading packet body.");
+        source.read_exact(&mut body).await.map_err(stream_error)?;
+        Self::from_body(&body)
+    }
 
-        debug!("Reading packet ID.");
-        source.read_exact(&mut buffer).await?;
-        let id = i32::from_le_bytes(buffer);
+    /// Decode a packet from the start of the given bytes.
+    ///
+    /// Returns the packet and the amount of bytes it occupied,
+    /// or `None` if the bytes do not contain a complete packet yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::MalformedPacket`] if the packet's header is invalid.
+    /// Returns [`Error::OversizedPacket`] if the packet exceeds [`Packet::MAX_SIZE`].
+    pub fn decode(bytes: &[u8]) -> Result<Option<(Self, usize)>, Error> {
+        Self::decode_with_limit(bytes, Self::MAX_SIZE)
+    }
+
+    /// Decode a packet of up to `max_size` bytes from the start of the given bytes.
+    ///
+    /// # Errors
+    ///
+    /// Behaves like [`Packet::decode`].
+    pub fn decode_with_limit(
+        bytes: &[u8],
+        max_size: usize,
+    ) -> Result<Option<(Self, usize)>, Error> {
+        let Some((size, rest)) = bytes.split_first_chunk::<{ size_of::<i32>() }>() else {
+            return Ok(None);
+        };
+        let size = body_size(*size, max_size)?;
+
+        let Some(body) = rest.get(..size) else {
+            return Ok(None);
+        };
+
+        Self::from_body(body).map(|packet| Some((packet, size_of::<i32>() + size)))
+    }
+
+    /// Parse a packet from the bytes following its size field.
+    fn from_body(body: &[u8]) -> Result<Self, Error> {
+        let [id0, id1, id2, id3, typ0, typ1, typ2, typ3, rest @ ..] = body else {
+            return Err(Error::malformed_packet("Packet body too short", body));
+        };
+        let [payload @ .., term0, term1] = rest else {
+            return Err(Error::malformed_packet("Packet body too short", body));
+        };
+
+        let id = i32::from_le_bytes([*id0, *id1, *id2, *id3]);
         trace!("Packet ID is {id}.");
 
-        debug!("Reading packet type.");
-        source.read_exact(&mut buffer).await?;
-        let type_id = i32::from_le_bytes(buffer);
-        let typ = ServerData::from_i32(type_id)
-            .ok_or_else(|| invalid_data(format!("Invalid packet type: {type_id:#010X}")))?;
+        let type_id = i32::from_le_bytes([*typ0, *typ1, *typ2, *typ3]);
+        let typ = ServerData::from_i32(type_id).ok_or_else(|| {
+            Error::malformed_packet(format!("Invalid packet type: {type_id:#010X}"), body)
+        })?;
         trace!("Packet type is {typ:?}.");
-
-        debug!("Reading payload.");
-        let mut payload =
-            vec![
-                0;
-                size.checked_sub(OFFSET)
-                    .ok_or_else(|| invalid_data(format!("Invalid payload size: {size}")))?
-            ];
-        source.read_exact(&mut payload).await?;
         trace!("Packet payload is {payload:?}.");
 
-        debug!("Reading terminator.");
-        let mut terminator = [0; 2];
-        source.read_exact(&mut terminator).await?;
+        let terminator = [*term0, *term1];
         trace!("Packet terminator is {terminator:?}.");
 
         if terminator != TERMINATOR {
             warn!("Received non-standard terminator: {terminator:?}");
         }
 
-        Ok(Self::new(id, typ, payload, terminator))
+        Ok(Self::new(id, typ, payload.to_vec(), terminator))
+    }
+
+    /// Returns the packet ID.
+    #[must_use]
+    pub const fn id(&self) -> i32 {
+        self.id
+    }
+
+    /// Returns the packet type.
+    #[must_use]
+    pub const fn typ(&self) -> ServerData {
+        self.typ
+    }
+
+    /// Returns the payload.
+    #[must_use]
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Returns the terminator.
+    ///
+    /// The terminator should consist of two null bytes, but some servers deviate from this.
+    #[must_use]
+    pub const fn terminator(&self) -> [u8; 2] {
+        self.terminator
     }
 
     /// Return the size of the packet.
+    ///
+    /// The size does not include the size field itself.
     #[must_use]
     pub const fn size(&self) -> usize {
         self.payload.len() + OFFSET
@@ -121,22 +208,43 @@ impl Packet {
     ///
     /// # Errors
     ///
-    /// Returns an [`std::io::Error`] if the validation fails.
-    pub fn validate(&self, id: i32, quirks: Quirks) -> std::io::Result<()> {
+    /// Returns [`Error::PacketIdMismatch`] if the validation fails.
+    pub fn validate(&self, id: i32, quirks: Quirks) -> Result<(), Error> {
         if self.id == id {
             Ok(())
         } else if self.id == 0x00 && quirks.contains(Quirks::PALWORLD) {
             debug!("Packet ID does not match, but accepting packet due to Palworld quirk.");
             Ok(())
         } else {
-            Err(invalid_data(format!(
-                "Packet ID mismatch: {} != {id}",
-                self.id
-            )))
+            Err(Error::PacketIdMismatch {
+                expected: id,
+                actual: self.id,
+            })
         }
     }
 }
 
+/// Returns the amount of bytes following the size field of a packet.
+fn body_size(buffer: [u8; 4], max_size: usize) -> Result<usize, Error> {
+    let size: usize = i32::from_le_bytes(buffer)
+        .try_into()
+        .map_err(|error| Error::malformed_packet(error, &buffer))?;
+    trace!("Packet size is {size}.");
+
+    if size < OFFSET {
+        return Err(Error::malformed_packet(
+            format!("Invalid size: {size}"),
+            &buffer,
+        ));
+    }
+
+    if size > max_size {
+        return Err(Error::OversizedPacket { size, max_size });
+    }
+
+    Ok(size)
+}
+
 impl TryFrom<Packet> for Vec<u8> {
     type Error = TryFromIntError;
 
@@ -150,3 +258,52 @@ impl TryFrom<Packet> for Vec<u8> {
         Ok(bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Packet, ServerData};
+    use crate::Error;
+
+    #[test]
+    fn test_decode_round_trip() {
+        let packet = Packet::command(42, b"list");
+        let mut bytes: Vec<u8> = packet.clone().try_into().unwrap();
+        let size = bytes.len();
+        bytes.extend_from_slice(&[0x0e, 0x00]);
+
+        assert_eq!(Packet::decode(&bytes).unwrap(), Some((packet, size)));
+        assert_eq!(Packet::decode(&bytes[..size - 1]).unwrap(), None);
+        assert_eq!(Packet::decode(&bytes[..3]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert!(Packet::decode(&[0x02, 0, 0, 0, 0, 0]).is_err());
+        assert!(Packet::decode(&[0xff, 0xff, 0xff, 0xff]).is_err());
+
+        let mut bytes: Vec<u8> = Packet::new(1, ServerData::Auth, Vec::new(), [0, 0])
+            .try_into()
+            .unwrap();
+        bytes[8] = 0x07;
+        assert!(Packet::decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_decode_oversized() {
+        let bytes: Vec<u8> = Packet::command(1, &[b'a'; Packet::MAX_SIZE])
+            .try_into()
+            .unwrap();
+        assert!(matches!(
+            Packet::decode(&bytes),
+            Err(Error::OversizedPacket {
+                size: 4106,
+                max_size: Packet::MAX_SIZE
+            })
+        ));
+        assert!(matches!(
+            Packet::decode(&[0xff, 0xff, 0xff, 0x7f]),
+            Err(Error::OversizedPacket { .. })
+        ));
+        assert!(Packet::decode_with_limit(&bytes, 8192).unwrap().is_some());
+    }
+}
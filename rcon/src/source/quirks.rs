@@ -2,6 +2,11 @@ use std::str::FromStr;
 
 use bitflags::bitflags;
 
+use super::packet::Packet;
+
+/// Maximum packet size if [`Quirks::LARGE_PACKETS`] is enabled.
+const LARGE_MAX_SIZE: usize = 1 << 20;
+
 /// Quirks for Source RCON.
 #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub struct Quirks(u8);
@@ -13,6 +18,31 @@ bitflags! {
 
         /// Quirk for Palworld servers.
         const PALWORLD = 0b0000_0001;
+
+        /// Treat a response packet with a payload shorter than 4096 bytes as the last one.
+        ///
+        /// For servers that do not echo the sentinel packet, which is therefore not sent.
+        /// Full packets exceed the protocol's maximum size, so this requires [`Self::LARGE_PACKETS`].
+        const SHORT_PACKETS = 0b0000_0010;
+
+        /// Treat a response as complete if no further packet arrives within the idle timeout.
+        ///
+        /// For servers that do not echo the sentinel packet, which is therefore not sent.
+        const IDLE_TIMEOUT = 0b0000_0100;
+
+        /// Accept packets of up to 1 MiB instead of the protocol's maximum of 4096 bytes.
+        const LARGE_PACKETS = 0b0000_1000;
+
+        /// Quirks for Minecraft servers.
+        const MINECRAFT = Self::SHORT_PACKETS.bits()
+            | Self::IDLE_TIMEOUT.bits()
+            | Self::LARGE_PACKETS.bits();
+
+        /// Quirks for Rust servers.
+        const RUST = Self::IDLE_TIMEOUT.bits() | Self::LARGE_PACKETS.bits();
+
+        /// Quirks for Factorio servers.
+        const FACTORIO = Self::LARGE_PACKETS.bits();
     }
 }
 
@@ -23,3 +53,19 @@ impl FromStr for Quirks {
         Self::from_name(&s.to_uppercase()).ok_or_else(|| format!("Invalid quirk: {s}"))
     }
 }
+
+impl Quirks {
+    /// Returns `true` if the end of a response is detected by sending a sentinel packet.
+    pub(crate) const fn uses_sentinel(self) -> bool {
+        !self.intersects(Self::SHORT_PACKETS.union(Self::IDLE_TIMEOUT))
+    }
+
+    /// Returns the default maximum packet size.
+    pub(crate) const fn max_packet_size(self) -> usize {
+        if self.contains(Self::LARGE_PACKETS) {
+            LARGE_MAX_SIZE
+        } else {
+            Packet::MAX_SIZE
+        }
+    }
+}
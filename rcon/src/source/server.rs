@@ -0,0 +1,258 @@
+//! Server implementation for the `Source RCON` protocol.
+//!
+//! The server accepts TCP connections, authenticates clients with a password
+//! and dispatches their commands to a [`Handler`].
+//! It is intended to stand in for game servers in tests.
+
+use std::io::ErrorKind;
+use std::num::NonZero;
+use std::sync::Arc;
+
+use log::{debug, info, warn};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use super::packet::{AUTH_FAILURE, OFFSET};
+use super::util::{SPLIT_SIZE, stream_error};
+use super::{Packet, ServerData};
+use crate::Error;
+pub use crate::server::Handler;
+
+/// Default payload size at which responses are split, keeping packets within [`Packet::MAX_SIZE`].
+const DEFAULT_SPLIT_SIZE: NonZero<usize> =
+    NonZero::new(Packet::MAX_SIZE - OFFSET).expect("Default split size is non-zero.");
+
+/// Payload size at which Minecraft servers split responses.
+///
+/// Clients with [`Quirks::SHORT_PACKETS`](super::Quirks::SHORT_PACKETS) rely on it to detect the last packet.
+/// The resulting packets exceed [`Packet::MAX_SIZE`],
+/// so these clients also need [`Quirks::LARGE_PACKETS`](super::Quirks::LARGE_PACKETS).
+pub const SHORT_PACKETS_SPLIT_SIZE: NonZero<usize> =
+    NonZero::new(SPLIT_SIZE).expect("Split size is non-zero.");
+
+/// A `Source RCON` server.
+///
+/// # Examples
+///
+/// ```no_run
+/// use rcon::source::server::Server;
+/// use tokio::net::TcpListener;
+///
+/// # async fn serve() -> Result<(), rcon::Error> {
+/// let listener = TcpListener::bind("127.0.0.1:27015").await?;
+/// Server::new("secret", |command: Vec<u8>| async move { command })
+///     .serve(listener)
+///     .await
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Server<H> {
+    password: Vec<u8>,
+    handler: H,
+    split_size: NonZero<usize>,
+}
+
+impl<H> Server<H>
+where
+    H: Handler,
+{
+    /// Create a new server accepting the given password.
+    #[must_use]
+    pub fn new<P>(password: P, handler: H) -> Self
+    where
+        P: AsRef<[u8]>,
+    {
+        Self {
+            password: password.as_ref().to_vec(),
+            handler,
+            split_size: DEFAULT_SPLIT_SIZE,
+        }
+    }
+
+    /// Set the payload size at which responses are split into multiple packets.
+    ///
+    /// Defaults to the largest payload fitting into [`Packet::MAX_SIZE`].
+    /// Use [`SHORT_PACKETS_SPLIT_SIZE`] to serve clients with [`Quirks::SHORT_PACKETS`](super::Quirks::SHORT_PACKETS).
+    #[must_use]
+    pub const fn with_split_size(mut self, split_size: NonZero<usize>) -> Self {
+        self.split_size = split_size;
+        self
+    }
+
+    /// Accept connections on the listener and serve each of them in a separate task.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if accepting a connection fails.
+    pub async fn serve(self, listener: TcpListener) -> Result<(), Error> {
+        let server = Arc::new(self);
+
+        loop {
+            let (tcp_stream, address) = listener.accept().await?;
+            info!("Accepted connection from {address}");
+            let server = server.clone();
+
+            tokio::spawn(async move {
+                if let Err(error) = server.serve_connection(tcp_stream).await {
+                    warn!("Connection to {address} failed: {error}");
+                }
+
+                info!("Closed connection to {address}");
+            });
+        }
+    }
+
+    /// Serve a single client on an established transport until it disconnects.
+    ///
+    /// Commands are only accepted after a successful login.
+    /// If a client sends a command before logging in, the connection is closed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if any I/O or protocol errors occurred.
+    pub async fn serve_connection<T>(&self, mut transport: T) -> Result<(), Error>
+    where
+        T: AsyncRead + AsyncWrite + Unpin,
+    {
+        let mut authenticated = false;
+
+        loop {
+            let packet = match Packet::read_from(&mut transport).await {
+                Ok(packet) => packet,
+                Err(Error::Disconnected) => return Ok(()),
+                Err(error) => return Err(error),
+            };
+
+            match packet.typ {
+                ServerData::Auth => {
+                    authenticated = packet.payload == self.password;
+                    let id = if authenticated {
+                        packet.id
+                    } else {
+                        warn!("Client sent wrong password");
+                        AUTH_FAILURE
+                    };
+                    send(&mut transport, response(packet.id, Vec::new())).await?;
+                    send(
+                        &mut transport,
+                        Packet::new(
+                            id,
+                            ServerData::ExecCommandOrAuthResponse,
+                            Vec::new(),
+                            [0, 0],
+                        ),
+                    )
+                    .await?;
+                }
+                ServerData::ExecCommandOrAuthResponse if authenticated => {
+                    debug!("Handling command #{}", packet.id);
+                    let payload = self.handler.handle(packet.payload).await;
+
+                    for chunk in payload.chunks(self.split_size.get()) {
+                        send(&mut transport, response(packet.id, chunk.to_vec())).await?;
+                    }
+
+                    if payload.is_empty() {
+                        send(&mut transport, response(packet.id, Vec::new())).await?;
+                    }
+                }
+                ServerData::ExecCommandOrAuthResponse => {
+                    warn!("Closing connection of unauthenticated client");
+                    return transport.shutdown().await.map_err(stream_error);
+                }
+                ServerData::ResponseValue => {
+                    debug!("Echoing sentinel packet #{}", packet.id);
+                    send(&mut transport, packet).await?;
+                }
+            }
+        }
+    }
+}
+
+const fn response(id: i32, payload: Vec<u8>) -> Packet {
+    Packet::new(id, ServerData::ResponseValue, payload, [0, 0])
+}
+
+async fn send<T>(transport: &mut T, packet: Packet) -> Result<(), Error>
+where
+    T: AsyncWrite + Unpin,
+{
+    let bytes: Vec<u8> = packet
+        .try_into()
+        .map_err(|error| std::io::Error::new(ErrorKind::InvalidInput, error))?;
+    transport.write_all(&bytes).await.map_err(stream_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::{SHORT_PACKETS_SPLIT_SIZE, Server};
+    use crate::source::{Client, Quirks};
+    use crate::{Error, RCon};
+
+    async fn handle(command: Vec<u8>) -> Vec<u8> {
+        if command == b"long" {
+            vec![b'a'; 10_000]
+        } else {
+            command
+        }
+    }
+
+    async fn spawn_server() -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(Server::new("secret", handle).serve(listener));
+        address
+    }
+
+    #[tokio::test]
+    async fn test_server() {
+        let address = spawn_server().await;
+        let mut client = Client::builder()
+            .connect(address)
+            .await
+            .unwrap()
+            .login("secret")
+            .await
+            .unwrap();
+
+        assert_eq!(client.peer_addr().unwrap(), address);
+        assert_eq!(client.run_utf8("list").await.unwrap(), "list");
+        assert_eq!(client.run("long").await.unwrap(), vec![b'a'; 10_000]);
+        assert_eq!(client.run_utf8("").await.unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn test_server_wrong_password() {
+        let address = spawn_server().await;
+        let connection = Client::builder().connect(address).await.unwrap();
+
+        assert!(matches!(
+            connection.login("wrong").await,
+            Err(Error::AuthenticationFailed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_server_short_packets() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(
+            Server::new("secret", handle)
+                .with_split_size(SHORT_PACKETS_SPLIT_SIZE)
+                .serve(listener),
+        );
+        let mut client = Client::builder()
+            .quirk(Quirks::MINECRAFT)
+            .connect(address)
+            .await
+            .unwrap()
+            .login("secret")
+            .await
+            .unwrap();
+
+        assert_eq!(client.run("long").await.unwrap(), vec![b'a'; 10_000]);
+        assert_eq!(client.run_utf8("list").await.unwrap(), "list");
+    }
+}
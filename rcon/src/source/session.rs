@@ -0,0 +1,408 @@
+//! Transport-independent state of a `Source RCON` client.
+//!
+//! The [`Session`] implements the request and response handling of the protocol without performing any I/O.
+//! The async and the blocking client encode requests with it, feed it the bytes read from their transport
+//! and pass it the decoded packets until a response is complete.
+
+use std::io::ErrorKind;
+use std::time::Duration;
+
+use log::{debug, error, trace, warn};
+use rand::random;
+
+use super::packet::{AUTH_FAILURE, Packet};
+use super::quirks::Quirks;
+use super::server_data::ServerData;
+use super::util::SPLIT_SIZE;
+use crate::Error;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Settings and request state of a client.
+#[derive(Debug)]
+pub struct Session {
+    quirks: Quirks,
+    timeout: Option<Duration>,
+    idle_timeout: Duration,
+    max_packet_size: Option<usize>,
+    buffer: Vec<Packet>,
+    input: Vec<u8>,
+    closed: bool,
+    pending: Option<i32>,
+    stale: Vec<i32>,
+}
+
+impl Session {
+    pub const fn new() -> Self {
+        Self {
+            quirks: Quirks::NONE,
+            timeout: Some(DEFAULT_TIMEOUT),
+            idle_timeout: DEFAULT_IDLE_TIMEOUT,
+            max_packet_size: None,
+            buffer: Vec::new(),
+            input: Vec::new(),
+            closed: false,
+            pending: None,
+            stale: Vec::new(),
+        }
+    }
+
+    pub const fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub const fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    pub const fn idle_timeout(&self) -> Duration {
+        self.idle_timeout
+    }
+
+    pub const fn set_idle_timeout(&mut self, idle_timeout: Duration) {
+        self.idle_timeout = idle_timeout;
+    }
+
+    pub const fn max_packet_size(&self) -> usize {
+        match self.max_packet_size {
+            Some(max_packet_size) => max_packet_size,
+            None => self.quirks.max_packet_size(),
+        }
+    }
+
+    pub const fn set_max_packet_size(&mut self, max_packet_size: usize) {
+        self.max_packet_size = Some(max_packet_size);
+    }
+
+    pub const fn quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    pub fn enable_quirk(&mut self, quirk: Quirks) {
+        self.quirks.insert(quirk);
+    }
+
+    /// Returns the buffer to append bytes read from the transport to.
+    pub const fn input(&mut self) -> &mut Vec<u8> {
+        &mut self.input
+    }
+
+    /// Begin a login and return the bytes to send.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the connection has been closed or the password is too long.
+    pub fn login(&mut self, password: &[u8]) -> Result<Vec<u8>, Error> {
+        let login = Packet::login(random(), password);
+        self.begin(login.id)?;
+        encode(login)
+    }
+
+    /// Processes a packet received in response to the login.
+    ///
+    /// Returns whether the server accepted the password once its verdict arrived.
+    pub fn login_response(&mut self, packet: &Packet) -> Option<bool> {
+        if packet.typ != ServerData::ExecCommandOrAuthResponse {
+            debug!("Skipping packet preceding the login response");
+            return None;
+        }
+
+        self.pending = None;
+        Some(packet.id != AUTH_FAILURE)
+    }
+
+    /// Begin a command and return the bytes to send.
+    ///
+    /// Unless the quirks prevent it, the bytes include a sentinel packet marking the end of the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the connection has been closed or the command is too long.
+    pub fn command(&mut self, command: &[u8]) -> Result<Vec<u8>, Error> {
+        let command = Packet::command(random(), command);
+        let sentinel = command.sentinel();
+        self.begin(command.id)?;
+        let mut bytes = encode(command)?;
+
+        if self.quirks.uses_sentinel() {
+            bytes.extend(encode(sentinel)?);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Processes a packet received in response to the pending command.
+    ///
+    /// Returns the concatenated payload once the packet marks the end of the response
+    /// according to the quirks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the packet does not belong to the command.
+    pub fn command_response(&mut self, packet: Packet) -> Result<Option<Vec<u8>>, Error> {
+        let Some(id) = self.pending else {
+            warn!("Discarding packet without pending command: {packet:?}");
+            return Ok(None);
+        };
+
+        let response = match packet.typ {
+            ServerData::ExecCommandOrAuthResponse => Some(packet.payload),
+            ServerData::ResponseValue => {
+                // Check for sentinel ID, which is one ahead of the command ID.
+                if packet.id == id.wrapping_add(1) {
+                    debug!("Received sentinel packet");
+                    Some(self.drain())
+                } else {
+                    packet.validate(id, self.quirks)?;
+                    debug!("Received data packet");
+                    let last = self.quirks.contains(Quirks::SHORT_PACKETS)
+                        && packet.payload.len() < SPLIT_SIZE;
+                    self.buffer.push(packet);
+
+                    if last {
+                        debug!("Received short packet");
+                        Some(self.drain())
+                    } else {
+                        None
+                    }
+                }
+            }
+            ServerData::Auth => {
+                error!("Received unexpected packet type: {:?}", ServerData::Auth);
+                trace!("Packet: {packet:?}");
+                None
+            }
+        };
+
+        if response.is_some() {
+            self.pending = None;
+        }
+
+        Ok(response)
+    }
+
+    /// Returns the time to wait for further packets of the pending response,
+    /// after which it is considered complete.
+    ///
+    /// Returns `None` unless [`Quirks::IDLE_TIMEOUT`] is enabled and parts of the response have been received.
+    pub fn pending_idle_timeout(&self) -> Option<Duration> {
+        (self.quirks.contains(Quirks::IDLE_TIMEOUT) && !self.buffer.is_empty())
+            .then_some(self.idle_timeout)
+    }
+
+    /// Completes the pending response after no further packets arrived within the idle timeout.
+    pub fn idle(&mut self) -> Vec<u8> {
+        debug!("No further packets within idle timeout");
+        self.pending = None;
+        self.drain()
+    }
+
+    /// Decode the next packet from the received bytes that is not a late response to an abandoned request.
+    ///
+    /// Returns `None` if more bytes are needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Disconnected`] if the connection has been closed.
+    /// Returns another [`Error`] if the received bytes are not a valid packet.
+    /// The session is then closed, and the caller should shut down the transport,
+    /// since the stream cannot be resynchronised without knowing the packet boundaries.
+    pub fn decode(&mut self) -> Result<Option<Packet>, Error> {
+        loop {
+            if self.closed {
+                return Err(Error::Disconnected);
+            }
+
+            match Packet::decode_with_limit(&self.input, self.max_packet_size()) {
+                Ok(Some((packet, size))) => {
+                    self.input.drain(..size);
+
+                    if !self.is_stale(&packet) {
+                        return Ok(Some(packet));
+                    }
+                }
+                Ok(None) => return Ok(None),
+                Err(error) => {
+                    warn!("Closing connection after receiving an invalid packet");
+                    self.closed = true;
+                    self.input.clear();
+                    return Err(error);
+                }
+            }
+        }
+    }
+
+    /// Mark a request as pending.
+    ///
+    /// If the previous request is still pending, its caller gave up waiting, e.g. due to a timeout.
+    /// Its late responses are discarded when decoding the responses to later requests.
+    fn begin(&mut self, id: i32) -> Result<(), Error> {
+        if self.closed {
+            return Err(Error::Disconnected);
+        }
+
+        if let Some(stale) = self.pending.replace(id) {
+            debug!("Discarding responses to abandoned request #{stale}");
+            self.stale.push(stale);
+            self.buffer.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether the packet is a late response to an abandoned request.
+    ///
+    /// Requests are forgotten once their last response has been seen.
+    fn is_stale(&mut self, packet: &Packet) -> bool {
+        let Some(index) = self
+            .stale
+            .iter()
+            .position(|&id| packet.id == id || packet.id == id.wrapping_add(1))
+        else {
+            return false;
+        };
+
+        trace!("Discarding late packet: {packet:?}");
+
+        if packet.id == self.stale[index].wrapping_add(1)
+            || packet.typ == ServerData::ExecCommandOrAuthResponse
+        {
+            self.stale.swap_remove(index);
+        }
+
+        true
+    }
+
+    /// Concatenates the payloads of the buffered packets, leaving the buffer empty.
+    fn drain(&mut self) -> Vec<u8> {
+        self.buffer
+            .drain(..)
+            .flat_map(|response| response.payload)
+            .collect()
+    }
+}
+
+fn encode(packet: Packet) -> Result<Vec<u8>, Error> {
+    let bytes: Vec<u8> = packet
+        .try_into()
+        .map_err(|error| std::io::Error::new(ErrorKind::InvalidInput, error))?;
+    debug!("Sending bytes: {bytes:?}");
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Session;
+    use crate::source::packet::{AUTH_FAILURE, Packet};
+    use crate::source::{Quirks, ServerData};
+
+    /// Decode the packets of the given request bytes.
+    fn decode_request(bytes: Vec<u8>) -> Vec<Packet> {
+        let mut session = Session::new();
+        session.input().extend(bytes);
+        std::iter::from_fn(|| session.decode().unwrap()).collect()
+    }
+
+    fn response(id: i32, payload: &[u8]) -> Packet {
+        Packet::new(id, ServerData::ResponseValue, payload.to_vec(), [0, 0])
+    }
+
+    #[test]
+    fn test_login() {
+        let mut session = Session::new();
+        let request = decode_request(session.login(b"secret").unwrap());
+        let id = request[0].id;
+
+        assert_eq!(session.login_response(&response(id, b"")), None);
+        assert_eq!(
+            session.login_response(&Packet::new(
+                AUTH_FAILURE,
+                ServerData::ExecCommandOrAuthResponse,
+                Vec::new(),
+                [0, 0]
+            )),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_command_with_sentinel() {
+        let mut session = Session::new();
+        let request = decode_request(session.command(b"list").unwrap());
+        let [command, sentinel] = request.as_slice() else {
+            panic!("Expected command and sentinel packets, got {request:?}");
+        };
+
+        assert_eq!(
+            session
+                .command_response(response(command.id, b"a"))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            session
+                .command_response(response(command.id, b"b"))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            session
+                .command_response(response(sentinel.id, b""))
+                .unwrap(),
+            Some(b"ab".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_command_with_short_packets() {
+        let mut session = Session::new();
+        session.enable_quirk(Quirks::MINECRAFT);
+        let request = decode_request(session.command(b"list").unwrap());
+        let [command] = request.as_slice() else {
+            panic!("Expected a single command packet, got {request:?}");
+        };
+
+        assert_eq!(
+            session
+                .command_response(response(command.id, &[b'a'; 4096]))
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            session
+                .command_response(response(command.id, b"b"))
+                .unwrap(),
+            Some([[b'a'; 4096].as_slice(), b"b"].concat())
+        );
+    }
+
+    #[test]
+    fn test_discard_stale_responses() {
+        let mut session = Session::new();
+        let stale = decode_request(session.command(b"list").unwrap());
+        let request = decode_request(session.command(b"help").unwrap());
+
+        for packet in [
+            response(stale[0].id, b"late"),
+            response(stale[1].id, b""),
+            response(request[0].id, b"help"),
+        ] {
+            let bytes: Vec<u8> = packet.try_into().unwrap();
+            session.input().extend(bytes);
+        }
+
+        let packet = session.decode().unwrap().unwrap();
+        assert_eq!(packet.payload(), b"help");
+        assert!(session.decode().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_close_after_invalid_packet() {
+        let mut session = Session::new();
+        session.input().extend([0xFF; 16]);
+
+        assert!(session.decode().is_err());
+        assert!(session.command(b"list").is_err());
+    }
+}
@@ -1,9 +1,22 @@
-use std::io::{Error, ErrorKind};
-
-/// Converts any fitting error type into an `InvalidData` error.
-pub fn invalid_data<T>(reason: T) -> Error
-where
-    T: Into<Box<dyn std::error::Error + Send + Sync>>,
-{
-    Error::new(ErrorKind::InvalidData, reason)
+use std::io::ErrorKind;
+
+use crate::Error;
+
+/// Payload size at which servers split responses into multiple packets.
+pub const SPLIT_SIZE: usize = 4096;
+
+/// Amount of bytes to reserve in the read buffer before reading from the stream.
+pub const READ_SIZE: usize = 4096;
+
+/// Converts an I/O error on the TCP stream into an [`Error`].
+///
+/// Errors indicating that the server closed the connection are mapped to [`Error::Disconnected`].
+pub fn stream_error(error: std::io::Error) -> Error {
+    match error.kind() {
+        ErrorKind::UnexpectedEof
+        | ErrorKind::BrokenPipe
+        | ErrorKind::ConnectionReset
+        | ErrorKind::ConnectionAborted => Error::Disconnected,
+        _ => Error::Io(error),
+    }
 }
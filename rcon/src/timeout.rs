@@ -0,0 +1,22 @@
+//! Timeout handling for requests.
+
+use std::future::Future;
+use std::time::Duration;
+
+use crate::Error;
+
+/// Await the given future, failing with [`Error::TimedOut`] if it does not complete in time.
+///
+/// If `duration` is `None`, the future is awaited indefinitely.
+pub async fn timeout<F, T>(duration: Option<Duration>, future: F) -> Result<T, Error>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    let Some(duration) = duration else {
+        return future.await;
+    };
+
+    tokio::time::timeout(duration, future)
+        .await
+        .map_err(|_| Error::TimedOut)?
+}
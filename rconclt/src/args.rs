@@ -1,5 +1,3 @@
-use std::net::SocketAddr;
-
 use clap::Parser;
 use rcon::source;
 use rpassword::prompt_password;
@@ -7,8 +5,11 @@ use rpassword::prompt_password;
 #[derive(Debug, Parser)]
 #[command(author, version, about = "An RCon CLI client.")]
 pub struct Args {
-    #[arg(index = 1, help = "The server address to connect to")]
-    server: SocketAddr,
+    #[arg(
+        index = 1,
+        help = "The server address to connect to, e.g. \"example.org:25575\""
+    )]
+    server: String,
     #[arg(short, long, help = "The password for the RCON server")]
     password: Option<String>,
     #[clap(subcommand)]
@@ -16,8 +17,8 @@ pub struct Args {
 }
 
 impl Args {
-    pub const fn server(&self) -> SocketAddr {
-        self.server
+    pub fn server(&self) -> &str {
+        &self.server
     }
 
     pub fn password(&self) -> std::io::Result<String> {
@@ -48,3 +49,11 @@ pub enum Protocol {
         command: Vec<String>,
     },
 }
+
+impl Protocol {
+    pub fn command(&self) -> &[String] {
+        match self {
+            Self::Source { command, .. } | Self::BattlEye { command } => command,
+        }
+    }
+}
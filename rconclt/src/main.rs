@@ -1,12 +1,14 @@
 //! An example `RCON` client supporting both `Source RCON` and `BattlEye Rcon`.
 
-use std::io::{Error, Write, stdout};
+use std::fmt::Display;
+use std::io::{Write, stdout};
 use std::process::ExitCode;
 
 use args::{Args, Protocol};
 use clap::Parser;
 use log::error;
-use rcon::{RCon, battleye, source};
+use rcon::any::AnyConnection;
+use rcon::{Connect, Error, RCon, battleye, source};
 
 mod args;
 
@@ -19,7 +21,7 @@ async fn main() -> ExitCode {
         stdout()
             .lock()
             .write_all(&response)
-            .map_err(io_error_to_exit_code)
+            .map_err(error_to_exit_code)
     }) {
         return code;
     }
@@ -28,62 +30,43 @@ async fn main() -> ExitCode {
 }
 
 async fn run(args: &Args) -> Result<Vec<u8>, ExitCode> {
-    match args.protocol() {
-        Protocol::BattlEye { command } => {
-            let client = battleye::Client::connect(args.server())
-                .await
-                .map_err(io_error_to_exit_code)?;
-            run_impl(
-                client,
-                args.password().map_err(io_error_to_exit_code)?,
-                command,
-            )
+    let connection: AnyConnection = match args.protocol() {
+        Protocol::BattlEye { .. } => battleye::Connection::connect(args.server())
             .await
-        }
-        Protocol::Source { command, quirks } => {
-            let mut client = source::Client::connect(args.server())
-                .await
-                .map_err(io_error_to_exit_code)?;
-
-            if let Some(quirks) = quirks.iter().copied().reduce(|acc, quirk| acc | quirk) {
-                client.enable_quirk(quirks);
-            }
-
-            run_impl(
-                client,
-                args.password().map_err(io_error_to_exit_code)?,
-                command,
-            )
+            .map_err(error_to_exit_code)?
+            .into(),
+        Protocol::Source { quirks, .. } => quirks
+            .iter()
+            .fold(source::Client::builder(), |builder, &quirk| {
+                builder.quirk(quirk)
+            })
+            .connect(args.server())
             .await
-        }
-    }
-}
+            .map_err(error_to_exit_code)?
+            .into(),
+    };
 
-async fn run_impl<T>(
-    mut client: T,
-    password: String,
-    command: &[String],
-) -> Result<Vec<u8>, ExitCode>
-where
-    T: RCon + Send,
-{
-    if !client
-        .login(&password)
-        .await
-        .map_err(io_error_to_exit_code)?
-    {
-        error!("Login failed.");
-        return Err(ExitCode::from(4));
-    }
+    let password = args.password().map_err(error_to_exit_code)?;
+    let mut client = connection.login(&password).await.map_err(|error| {
+        if matches!(error, Error::AuthenticationFailed) {
+            error!("Login failed.");
+            ExitCode::from(4)
+        } else {
+            error_to_exit_code(error)
+        }
+    })?;
 
     client
-        .run(command.join(" "))
+        .run(args.protocol().command().join(" "))
         .await
-        .map_err(io_error_to_exit_code)
+        .map_err(error_to_exit_code)
 }
 
 #[allow(clippy::needless_pass_by_value)]
-fn io_error_to_exit_code(error: Error) -> ExitCode {
+fn error_to_exit_code<T>(error: T) -> ExitCode
+where
+    T: Display,
+{
     error!("{error}");
     ExitCode::from(5)
 }